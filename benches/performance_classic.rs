@@ -8,6 +8,7 @@ use generic_anon_ake::classic::{
         round_1, round_2, round_3, round_4, round_5, round_6,
     },
 };
+use rand::rngs::OsRng;
 
 const SAMPLES: usize = 10;
 const LOW_LIMIT: u32 = 6;
@@ -28,29 +29,30 @@ fn bench_1(c: &mut Criterion) {
         let config: Config = Config::new(users);
         let mut lengths = vec![];
 
-        let (mut server, mut client) = registration(&config);
+        let (mut server, mut clients) = registration(&config, &mut OsRng).unwrap();
+        let mut client = clients.remove(0);
 
-        let m1 = round_1(&mut client);
-        lengths.push(get_m1_length(&m1));
-        client.send_m1(m1, &mut server);
+        let m1 = round_1(&mut client, &config, &mut OsRng).unwrap();
+        lengths.push(get_m1_length(&config, &m1));
+        let handle = client.send_m1(m1, &mut server);
 
-        let m2 = round_2(&mut server, &config, client.get_id());
-        lengths.push(get_m2_length(&m2));
+        let m2 = round_2(&mut server, &config, handle, &mut OsRng).unwrap();
+        lengths.push(get_m2_length(&config, &m2));
         server.send_m2(m2, &mut client);
 
-        let m3 = round_3(&mut client, false);
-        lengths.push(get_m3_length(&m3));
+        let m3 = round_3(&mut client, &config, &mut OsRng, false).unwrap();
+        lengths.push(get_m3_length(&config, &m3));
         client.send_m3(m3, &mut server);
 
-        let m4 = round_4(&mut server);
-        lengths.push(get_m4_length(&m4));
+        let m4 = round_4(&mut server, &config, handle).unwrap();
+        lengths.push(get_m4_length(&config, &m4));
         server.send_m4(m4, &mut client);
 
-        let m5 = round_5(&mut client, &config, false);
-        lengths.push(get_m5_length(&m5));
+        let m5 = round_5(&mut client, &config, &mut OsRng, false).unwrap();
+        lengths.push(get_m5_length(&config, &m5));
         client.send_m5(m5, &mut server);
 
-        round_6(&mut server, client.get_id(), false);
+        round_6(&mut server, &config, handle, false).unwrap();
 
         let data = lengths
             .iter()
@@ -69,35 +71,35 @@ fn bench_1(c: &mut Criterion) {
         // group.bench_with_input(
         //     BenchmarkId::new("Registration", parameter_string.clone()),
         //     &_x0,
-        //     |b, _| b.iter(|| registration(&config)),
+        //     |b, _| b.iter(|| registration(&config, &mut OsRng)),
         // );
 
         let _x1 = (0, 0);
         group.bench_with_input(
             BenchmarkId::new("Round 1", parameter_string.clone()),
             &_x1,
-            |b, _| b.iter(|| round_1(&mut client)),
+            |b, _| b.iter(|| round_1(&mut client, &config, &mut OsRng)),
         );
 
         let _x2 = (0, 0);
         group.bench_with_input(
             BenchmarkId::new("Round 2", parameter_string.clone()),
             &_x2,
-            |b, _| b.iter(|| round_2(&mut server, &config, client.get_id())),
+            |b, _| b.iter(|| round_2(&mut server, &config, handle, &mut OsRng)),
         );
 
         let _x3 = (0, 0);
         group.bench_with_input(
             BenchmarkId::new("Round 3", parameter_string.clone()),
             &_x3,
-            |b, _| b.iter(|| round_3(&mut client, false)),
+            |b, _| b.iter(|| round_3(&mut client, &config, &mut OsRng, false)),
         );
 
         let _x4 = (0, 0);
         group.bench_with_input(
             BenchmarkId::new("Round 4", parameter_string.clone()),
             &_x4,
-            |b, _| b.iter(|| round_4(&mut server)),
+            |b, _| b.iter(|| round_4(&mut server, &config, handle)),
         );
     }
     group.finish();
@@ -116,25 +118,26 @@ fn bench_2(c: &mut Criterion) {
     {
         let config: Config = Config::new(users);
 
-        let (mut server, mut client) = registration(&config);
+        let (mut server, mut clients) = registration(&config, &mut OsRng).unwrap();
+        let mut client = clients.remove(0);
 
-        let m1 = round_1(&mut client);
-        client.send_m1(m1, &mut server);
+        let m1 = round_1(&mut client, &config, &mut OsRng).unwrap();
+        let handle = client.send_m1(m1, &mut server);
 
-        let m2 = round_2(&mut server, &config, client.get_id());
+        let m2 = round_2(&mut server, &config, handle, &mut OsRng).unwrap();
         server.send_m2(m2, &mut client);
 
-        let m3 = round_3(&mut client, false);
+        let m3 = round_3(&mut client, &config, &mut OsRng, false).unwrap();
         client.send_m3(m3, &mut server);
 
-        let m4 = round_4(&mut server);
+        let m4 = round_4(&mut server, &config, handle).unwrap();
 
         server.send_m4(m4, &mut client);
 
-        let m5 = round_5(&mut client, &config, false);
+        let m5 = round_5(&mut client, &config, &mut OsRng, false).unwrap();
         client.send_m5(m5, &mut server);
 
-        round_6(&mut server, client.get_id(), false);
+        round_6(&mut server, &config, handle, false).unwrap();
 
         let parameter_string = format!("{}", users);
 
@@ -142,14 +145,14 @@ fn bench_2(c: &mut Criterion) {
         group.bench_with_input(
             BenchmarkId::new("Round 5", parameter_string.clone()),
             &_x5,
-            |b, _| b.iter(|| round_5(&mut client, &config, false)),
+            |b, _| b.iter(|| round_5(&mut client, &config, &mut OsRng, false)),
         );
 
         let _x6 = (0, 0);
         group.bench_with_input(
             BenchmarkId::new("Round 6", parameter_string.clone()),
             &_x6,
-            |b, _| b.iter(|| round_6(&mut server, client.get_id(), false)),
+            |b, _| b.iter(|| round_6(&mut server, &config, handle, false)),
         );
     }
     group.finish();
@@ -13,18 +13,47 @@ use generic_anon_ake::{
         supported_algs::get_kem_algorithm,
     },
 };
+use rand::rngs::OsRng;
 
 const SAMPLES: usize = 10;
 const LOW_LIMIT: u32 = 6;
 const UPP_LIMIT: u32 = 11; // Fix: Change to 17
 const WARMUP: u64 = 1;
-const ALGS: [(&'static str, &'static str); 6] = [
+const ALGS: [(&str, &str); 24] = [
+    ("ML-KEM-1024", "ML-DSA-87"),
+    ("ML-KEM-768", "ML-DSA-65"),
+    ("ML-KEM-512", "ML-DSA-44"),
     ("Kyber1024", "Dilithium5"),
     ("Kyber768", "Dilithium3"),
     ("Kyber512", "Dilithium2"),
     ("ClassicMcEliece6960119f", "Dilithium5"),
     ("ClassicMcEliece460896f", "Dilithium3"),
     ("ClassicMcEliece348864f", "Dilithium2"),
+    ("ML-KEM-1024", "Falcon1024"),
+    ("ML-KEM-512", "Falcon512"),
+    ("ML-KEM-512", "SphincsSha2128fSimple"),
+    ("ML-KEM-512", "SphincsSha2128sSimple"),
+    ("ML-KEM-512", "SphincsShake128fSimple"),
+    ("ML-KEM-512", "SphincsShake128sSimple"),
+    ("Hqc256", "ML-DSA-87"),
+    ("Hqc192", "ML-DSA-65"),
+    ("Hqc128", "ML-DSA-44"),
+    ("BikeL5", "ML-DSA-87"),
+    ("BikeL3", "ML-DSA-65"),
+    ("BikeL1", "ML-DSA-44"),
+    ("FrodoKem1344Shake", "ML-DSA-87"),
+    ("FrodoKem976Shake", "ML-DSA-65"),
+    ("FrodoKem640Aes", "ML-DSA-44"),
+];
+// KEM of the PKE, KEM of the CCA-PKE and signature scheme. The PKE is used
+// once per client in round 2, the CCA-PKE once per session.
+const SPLIT_ALGS: [(&str, &str, &str); 6] = [
+    ("ClassicMcEliece6960119f", "ML-KEM-1024", "ML-DSA-87"),
+    ("ClassicMcEliece460896f", "ML-KEM-768", "ML-DSA-65"),
+    ("ClassicMcEliece348864f", "ML-KEM-512", "ML-DSA-44"),
+    ("ML-KEM-512", "FrodoKem640Shake", "ML-DSA-44"),
+    ("ML-KEM-512", "Hqc128", "ML-DSA-44"),
+    ("ML-KEM-512", "ClassicMcEliece348864f", "ML-DSA-44"),
 ];
 
 fn bench_1(c: &mut Criterion) {
@@ -44,30 +73,31 @@ fn bench_1(c: &mut Criterion) {
             let config: Config = Config::new(users, kemalg, sigalg);
             let mut lengths = vec![];
 
-            let (mut server, mut client) = registration(&config);
+            let (mut server, mut clients) = registration(&config, &mut OsRng).unwrap();
+            let mut client = clients.remove(0);
 
-            let m1 = round_1(&mut client);
-            lengths.push(get_m1_length(&m1));
-            client.send_m1(m1, &mut server);
+            let m1 = round_1(&mut client, &config, &mut OsRng).unwrap();
+            lengths.push(get_m1_length(&config, &m1));
+            let handle = client.send_m1(m1, &mut server);
 
-            let m2 = round_2(&mut server, &config, client.get_id());
-            lengths.push(get_m2_length(&m2));
+            let m2 = round_2(&mut server, &config, handle, &mut OsRng).unwrap();
+            lengths.push(get_m2_length(&config, &m2));
             server.send_m2(m2, &mut client);
 
-            let m3 = round_3(&mut client, &config, false);
-            lengths.push(get_m3_length(&m3));
+            let m3 = round_3(&mut client, &config, &mut OsRng, false).unwrap();
+            lengths.push(get_m3_length(&config, &m3));
             client.send_m3(m3, &mut server);
 
-            let m4 = round_4(&mut server, &config);
-            lengths.push(get_m4_length(&m4));
+            let m4 = round_4(&mut server, &config, handle).unwrap();
+            lengths.push(get_m4_length(&config, &m4));
 
             server.send_m4(m4, &mut client);
 
-            let m5 = round_5(&mut client, &config, false);
-            lengths.push(get_m5_length(&m5));
+            let m5 = round_5(&mut client, &config, &mut OsRng, false).unwrap();
+            lengths.push(get_m5_length(&config, &m5));
             client.send_m5(m5, &mut server);
 
-            round_6(&mut server, &config, client.get_id(), false);
+            round_6(&mut server, &config, handle, false).unwrap();
 
             let data = lengths
                 .iter()
@@ -86,35 +116,35 @@ fn bench_1(c: &mut Criterion) {
             // group.bench_with_input(
             //     BenchmarkId::new("Registration", parameter_string.clone()),
             //     &_x0,
-            //     |b, _| b.iter(|| registration(&config)),
+            //     |b, _| b.iter(|| registration(&config, &mut OsRng)),
             // );
 
             let _x1 = (0, 0);
             group.bench_with_input(
                 BenchmarkId::new("Round 1", parameter_string.clone()),
                 &_x1,
-                |b, _| b.iter(|| round_1(&mut client)),
+                |b, _| b.iter(|| round_1(&mut client, &config, &mut OsRng)),
             );
 
             let _x2 = (0, 0);
             group.bench_with_input(
                 BenchmarkId::new("Round 2", parameter_string.clone()),
                 &_x2,
-                |b, _| b.iter(|| round_2(&mut server, &config, client.get_id())),
+                |b, _| b.iter(|| round_2(&mut server, &config, handle, &mut OsRng)),
             );
 
             let _x3 = (0, 0);
             group.bench_with_input(
                 BenchmarkId::new("Round 3", parameter_string.clone()),
                 &_x3,
-                |b, _| b.iter(|| round_3(&mut client, &config, false)),
+                |b, _| b.iter(|| round_3(&mut client, &config, &mut OsRng, false)),
             );
 
             let _x4 = (0, 0);
             group.bench_with_input(
                 BenchmarkId::new("Round 4", parameter_string.clone()),
                 &_x4,
-                |b, _| b.iter(|| round_4(&mut server, &config)),
+                |b, _| b.iter(|| round_4(&mut server, &config, handle)),
             );
         }
     }
@@ -137,25 +167,26 @@ fn bench_2(c: &mut Criterion) {
             let sigalg = get_signature_algorithm(sigalg_str).unwrap();
             let config: Config = Config::new(users, kemalg, sigalg);
 
-            let (mut server, mut client) = registration(&config);
+            let (mut server, mut clients) = registration(&config, &mut OsRng).unwrap();
+            let mut client = clients.remove(0);
 
-            let m1 = round_1(&mut client);
-            client.send_m1(m1, &mut server);
+            let m1 = round_1(&mut client, &config, &mut OsRng).unwrap();
+            let handle = client.send_m1(m1, &mut server);
 
-            let m2 = round_2(&mut server, &config, client.get_id());
+            let m2 = round_2(&mut server, &config, handle, &mut OsRng).unwrap();
             server.send_m2(m2, &mut client);
 
-            let m3 = round_3(&mut client, &config, false);
+            let m3 = round_3(&mut client, &config, &mut OsRng, false).unwrap();
             client.send_m3(m3, &mut server);
 
-            let m4 = round_4(&mut server, &config);
+            let m4 = round_4(&mut server, &config, handle).unwrap();
 
             server.send_m4(m4, &mut client);
 
-            let m5 = round_5(&mut client, &config, false);
+            let m5 = round_5(&mut client, &config, &mut OsRng, false).unwrap();
             client.send_m5(m5, &mut server);
 
-            round_6(&mut server, &config, client.get_id(), false);
+            round_6(&mut server, &config, handle, false).unwrap();
 
             let parameter_string = format!("{}-{}-{}", kemalg_str, sigalg_str, users);
 
@@ -163,14 +194,100 @@ fn bench_2(c: &mut Criterion) {
             group.bench_with_input(
                 BenchmarkId::new("Round 5", parameter_string.clone()),
                 &_x5,
-                |b, _| b.iter(|| round_5(&mut client, &config, false)),
+                |b, _| b.iter(|| round_5(&mut client, &config, &mut OsRng, false)),
+            );
+
+            let _x6 = (0, 0);
+            group.bench_with_input(
+                BenchmarkId::new("Round 6", parameter_string.clone()),
+                &_x6,
+                |b, _| b.iter(|| round_6(&mut server, &config, handle, false)),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn bench_3(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Protocol_PQ_Split");
+
+    group.measurement_time(Duration::from_secs(WARMUP));
+    group.sample_size(SAMPLES);
+
+    for users in (LOW_LIMIT..UPP_LIMIT)
+        .map(|x| 2_u32.pow(x))
+        .rev()
+        .collect::<Vec<u32>>()
+    {
+        for (kemalg_str, ccapke_kemalg_str, sigalg_str) in SPLIT_ALGS {
+            let kemalg = get_kem_algorithm(kemalg_str).unwrap();
+            let ccapke_kemalg = get_kem_algorithm(ccapke_kemalg_str).unwrap();
+            let sigalg = get_signature_algorithm(sigalg_str).unwrap();
+            let mut config: Config = Config::new(users, kemalg, sigalg);
+            config.set_ccapke_algorithm(ccapke_kemalg);
+            let mut lengths = vec![];
+
+            let (mut server, mut clients) = registration(&config, &mut OsRng).unwrap();
+            let mut client = clients.remove(0);
+
+            let m1 = round_1(&mut client, &config, &mut OsRng).unwrap();
+            lengths.push(get_m1_length(&config, &m1));
+            let handle = client.send_m1(m1, &mut server);
+
+            let m2 = round_2(&mut server, &config, handle, &mut OsRng).unwrap();
+            lengths.push(get_m2_length(&config, &m2));
+            server.send_m2(m2, &mut client);
+
+            let m3 = round_3(&mut client, &config, &mut OsRng, false).unwrap();
+            lengths.push(get_m3_length(&config, &m3));
+            client.send_m3(m3, &mut server);
+
+            let m4 = round_4(&mut server, &config, handle).unwrap();
+            lengths.push(get_m4_length(&config, &m4));
+            server.send_m4(m4, &mut client);
+
+            let m5 = round_5(&mut client, &config, &mut OsRng, false).unwrap();
+            lengths.push(get_m5_length(&config, &m5));
+            client.send_m5(m5, &mut server);
+
+            round_6(&mut server, &config, handle, false).unwrap();
+
+            let data = lengths
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<String>>()
+                .join(",");
+            let filename = format!(
+                "target/criterion/pq-{}-{}-{}-{}.csv",
+                kemalg_str, ccapke_kemalg_str, sigalg_str, users
+            );
+            fs::write(filename, data).expect("Unable to write file");
+
+            let parameter_string = format!(
+                "{}-{}-{}-{}",
+                kemalg_str, ccapke_kemalg_str, sigalg_str, users
+            );
+
+            let _x2 = (0, 0);
+            group.bench_with_input(
+                BenchmarkId::new("Round 2", parameter_string.clone()),
+                &_x2,
+                |b, _| b.iter(|| round_2(&mut server, &config, handle, &mut OsRng)),
+            );
+
+            let _x5 = (0, 0);
+            group.bench_with_input(
+                BenchmarkId::new("Round 5", parameter_string.clone()),
+                &_x5,
+                |b, _| b.iter(|| round_5(&mut client, &config, &mut OsRng, false)),
             );
 
             let _x6 = (0, 0);
             group.bench_with_input(
                 BenchmarkId::new("Round 6", parameter_string.clone()),
                 &_x6,
-                |b, _| b.iter(|| round_6(&mut server, &config, client.get_id(), false)),
+                |b, _| b.iter(|| round_6(&mut server, &config, handle, false)),
             );
         }
     }
@@ -178,5 +295,5 @@ fn bench_2(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_1, bench_2);
+criterion_group!(benches, bench_1, bench_2, bench_3);
 criterion_main!(benches);
@@ -8,6 +8,7 @@ use generic_anon_ake::{
         supported_algs::{get_kem_algorithm, get_signature_algorithm},
     },
 };
+use rand::rngs::OsRng;
 
 fn bench_1(c: &mut Criterion) {
     let mut group = c.benchmark_group("PKE_PQ");
@@ -16,21 +17,36 @@ fn bench_1(c: &mut Criterion) {
     group.sample_size(100);
 
     for kemalg_str in [
+        "ML-KEM-1024",
+        "ML-KEM-768",
+        "ML-KEM-512",
         "Kyber1024",
         "Kyber768",
         "Kyber512",
         "ClassicMcEliece6960119f",
         "ClassicMcEliece460896f",
         "ClassicMcEliece348864f",
+        "BikeL1",
+        "BikeL3",
+        "BikeL5",
+        "Hqc128",
+        "Hqc192",
+        "Hqc256",
+        "FrodoKem640Aes",
+        "FrodoKem640Shake",
+        "FrodoKem976Aes",
+        "FrodoKem976Shake",
+        "FrodoKem1344Aes",
+        "FrodoKem1344Shake",
     ] {
         let kemalg = get_kem_algorithm(kemalg_str).unwrap();
         let (pk, sk) = kemalg.keypair().unwrap();
         let m: Vec<u8> = get_random_key32();
-        let (ct_kem, ct_dem, iv_tag) = ccapke_enc(&kemalg, &pk, &m);
+        let (ct_kem, ct_dem, iv_tag) = ccapke_enc(&kemalg, &pk, &m, &mut OsRng).unwrap();
 
-        ccapke_dec(&kemalg, sk.clone(), &ct_kem, &ct_dem, &iv_tag);
+        ccapke_dec(&kemalg, &sk, &ct_kem, &ct_dem, &iv_tag).unwrap();
 
-        let parameter_string = format!("{}", kemalg_str);
+        let parameter_string = kemalg_str.to_string();
 
         let _x0 = (0, 0);
         group.bench_with_input(
@@ -43,14 +59,14 @@ fn bench_1(c: &mut Criterion) {
         group.bench_with_input(
             BenchmarkId::new("ENC", parameter_string.clone()),
             &_x0,
-            |b, _| b.iter(|| ccapke_enc(&kemalg, &pk, &m)),
+            |b, _| b.iter(|| ccapke_enc(&kemalg, &pk, &m, &mut OsRng)),
         );
 
         let _x0 = (0, 0);
         group.bench_with_input(
             BenchmarkId::new("DEC", parameter_string.clone()),
             &_x0,
-            |b, _| b.iter(|| ccapke_dec(&kemalg, sk.clone(), &ct_kem, &ct_dem, &iv_tag)),
+            |b, _| b.iter(|| ccapke_dec(&kemalg, &sk, &ct_kem, &ct_dem, &iv_tag)),
         );
     }
     group.finish();
@@ -65,7 +81,7 @@ fn bench_2(c: &mut Criterion) {
     group.measurement_time(Duration::from_secs(1));
     group.sample_size(1000);
 
-    let parameter_string = format!("");
+    let parameter_string = String::new();
 
     let _x0 = (0, 0);
     group.bench_with_input(
@@ -83,7 +99,20 @@ fn bench_3(c: &mut Criterion) {
     group.measurement_time(Duration::from_secs(1));
     group.sample_size(1000);
 
-    for sigalg_str in ["Dilithium5", "Dilithium3", "Dilithium2"] {
+    for sigalg_str in [
+        "ML-DSA-87",
+        "ML-DSA-65",
+        "ML-DSA-44",
+        "Dilithium5",
+        "Dilithium3",
+        "Dilithium2",
+        "Falcon1024",
+        "Falcon512",
+        "SphincsSha2128fSimple",
+        "SphincsSha2128sSimple",
+        "SphincsShake128fSimple",
+        "SphincsShake128sSimple",
+    ] {
         let sigalg = get_signature_algorithm(sigalg_str).unwrap();
         let (pk, sk) = sigalg.keypair().unwrap();
         let m: Vec<u8> = get_random_key32();
@@ -91,7 +120,7 @@ fn bench_3(c: &mut Criterion) {
 
         sigalg.verify(&m, &signature, &pk).unwrap();
 
-        let parameter_string = format!("{}", sigalg_str);
+        let parameter_string = sigalg_str.to_string();
 
         let _x0 = (0, 0);
         group.bench_with_input(
@@ -8,6 +8,7 @@ use generic_anon_ake::{
     },
     common::utils::get_random_key32,
 };
+use rand::rngs::OsRng;
 
 fn bench_1(c: &mut Criterion) {
     let mut group = c.benchmark_group("PKE_CLASSIC");
@@ -15,26 +16,26 @@ fn bench_1(c: &mut Criterion) {
     group.measurement_time(Duration::from_secs(1));
     group.sample_size(1000);
 
-    let (pk, sk) = ccapke_gen();
+    let (pk, sk) = ccapke_gen(&mut OsRng);
     let m: Vec<u8> = get_random_key32();
-    let ct = ccapke_enc(&pk, &m);
+    let ct = ccapke_enc(&pk, &m, &mut OsRng).unwrap();
 
-    ccapke_dec(&sk, &ct);
+    ccapke_dec(&sk, &ct).unwrap();
 
-    let parameter_string = format!("");
+    let parameter_string = String::new();
 
     let _x0 = (0, 0);
     group.bench_with_input(
         BenchmarkId::new("KEYGEN", parameter_string.clone()),
         &_x0,
-        |b, _| b.iter(|| ccapke_gen()),
+        |b, _| b.iter(|| ccapke_gen(&mut OsRng)),
     );
 
     let _x0 = (0, 0);
     group.bench_with_input(
         BenchmarkId::new("ENC", parameter_string.clone()),
         &_x0,
-        |b, _| b.iter(|| ccapke_enc(&pk, &m)),
+        |b, _| b.iter(|| ccapke_enc(&pk, &m, &mut OsRng)),
     );
 
     let _x0 = (0, 0);
@@ -53,19 +54,19 @@ fn bench_2(c: &mut Criterion) {
     group.measurement_time(Duration::from_secs(1));
     group.sample_size(1000);
 
-    let (pk, sk) = sig_gen();
+    let (pk, sk) = sig_gen(&mut OsRng);
     let m: Vec<u8> = get_random_key32();
-    let signature = sig_sign(&sk, &m);
+    let signature = sig_sign(&sk, &m).unwrap();
 
     sig_vry(&pk, &m, &signature);
 
-    let parameter_string = format!("");
+    let parameter_string = String::new();
 
     let _x0 = (0, 0);
     group.bench_with_input(
         BenchmarkId::new("KEYGEN", parameter_string.clone()),
         &_x0,
-        |b, _| b.iter(|| sig_gen()),
+        |b, _| b.iter(|| sig_gen(&mut OsRng)),
     );
 
     let _x0 = (0, 0);
@@ -1,18 +1,75 @@
-use pke_ecies::{decrypt, encrypt, utils::generate_keypair, PublicKey, SecretKey};
+use libsecp256k1::{PublicKey, SecretKey};
+use rand::{CryptoRng, RngCore};
 
-pub fn ccapke_gen() -> (PublicKey, SecretKey) {
-    let (sk, pk) = generate_keypair();
+use crate::common::{
+    error::{AkeError, Result},
+    suite::CcaPke,
+};
 
-    (pk, sk)
+use super::{
+    ecies::{decrypt, encrypt},
+    pke::{secret_key_from_rng, Ecies},
+};
+
+pub fn ccapke_gen<R: RngCore + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey) {
+    let sk = secret_key_from_rng(rng);
+
+    (PublicKey::from_secret_key(&sk), sk)
+}
+
+pub fn ccapke_enc<R: RngCore + CryptoRng>(
+    pk: &PublicKey,
+    m: &[u8],
+    rng: &mut R,
+) -> Result<Vec<u8>> {
+    let sk = secret_key_from_rng(rng);
+    let r = (sk, PublicKey::from_secret_key(&sk));
+    encrypt(pk, m, &r)
 }
 
-pub fn ccapke_enc(pk: &PublicKey, m: &[u8]) -> Vec<u8> {
-    let r = generate_keypair();
-    let pk = &pk.serialize();
-    encrypt(pk, m, &r).unwrap()
+pub fn ccapke_dec(sk: &SecretKey, ct: &[u8]) -> Result<Vec<u8>> {
+    decrypt(sk, ct).map_err(|_| AkeError::Verification("CCA-PKE ciphertext"))
 }
 
-pub fn ccapke_dec(sk: &SecretKey, ct: &[u8]) -> Vec<u8> {
-    let sk = &sk.serialize();
-    decrypt(sk, ct).unwrap()
+impl CcaPke for Ecies {
+    type PublicKey = PublicKey;
+    type SecretKey = SecretKey;
+    type Ciphertext = Vec<u8>;
+
+    fn algorithm_name(&self) -> String {
+        "ECIES-secp256k1".to_string()
+    }
+
+    fn ccapke_gen<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Result<(PublicKey, SecretKey)> {
+        Ok(ccapke_gen(rng))
+    }
+
+    fn ccapke_enc<R: RngCore + CryptoRng>(
+        &self,
+        pk: &PublicKey,
+        m: &[u8],
+        rng: &mut R,
+    ) -> Result<Vec<u8>> {
+        ccapke_enc(pk, m, rng)
+    }
+
+    fn ccapke_dec(&self, sk: &SecretKey, ct: &Vec<u8>) -> Result<Vec<u8>> {
+        ccapke_dec(sk, ct)
+    }
+
+    fn public_key_to_bytes(&self, pk: &PublicKey) -> Vec<u8> {
+        pk.serialize().to_vec()
+    }
+
+    fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<PublicKey> {
+        PublicKey::parse_slice(bytes, None).map_err(|_| AkeError::Decoding("ECIES public key"))
+    }
+
+    fn ciphertext_to_bytes(&self, ct: &Vec<u8>) -> Vec<u8> {
+        ct.clone()
+    }
+
+    fn ciphertext_from_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>> {
+        Ok(bytes.to_vec())
+    }
 }
@@ -1,13 +1,71 @@
+use sha2::Sha256;
+
+use crate::common::{prf::PrfAlgorithm, suite::Suite};
+
+use super::{commitment::Sha256Commitment, pke::Ecies, sig::Ecdsa};
+
+pub const SUITE_ID: u8 = 0x02;
+
 pub struct Config {
     users_numbers: u32,
+    prf: PrfAlgorithm,
 }
 
 impl Config {
     pub fn new(users_numbers: u32) -> Self {
-        Config { users_numbers }
+        Config {
+            users_numbers,
+            prf: PrfAlgorithm::AesCtr,
+        }
+    }
+
+    // Both sides of a handshake must use the same PRF
+    pub fn set_prf_algorithm(&mut self, prf: PrfAlgorithm) {
+        self.prf = prf;
+    }
+
+    pub fn get_prf_algorithm(&self) -> PrfAlgorithm {
+        self.prf
     }
 
     pub fn get_users_number(&self) -> u32 {
         self.users_numbers
     }
 }
+
+impl Suite for Config {
+    type Pke = Ecies;
+    type CcaPke = Ecies;
+    type Sig = Ecdsa;
+    type Comm = Sha256Commitment;
+    type Prf = PrfAlgorithm;
+    type Hash = Sha256;
+
+    fn pke(&self) -> &Ecies {
+        &Ecies
+    }
+
+    fn ccapke(&self) -> &Ecies {
+        &Ecies
+    }
+
+    fn sig(&self) -> &Ecdsa {
+        &Ecdsa
+    }
+
+    fn comm(&self) -> &Sha256Commitment {
+        &Sha256Commitment
+    }
+
+    fn prf(&self) -> &PrfAlgorithm {
+        &self.prf
+    }
+
+    fn suite_id(&self) -> u8 {
+        SUITE_ID
+    }
+
+    fn get_users_number(&self) -> u32 {
+        self.users_numbers
+    }
+}
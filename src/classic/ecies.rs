@@ -0,0 +1,75 @@
+// ECIES over secp256k1 in the layout of ecies-rs:
+// eph_pk (65) || nonce (16) || tag (16) || AES-256-GCM ciphertext. The AES key
+// is HKDF-SHA256 of eph_pk || shared point, as in ecies-rs, which therefore
+// decrypts these ciphertexts. The nonce is the next 16 bytes of the same HKDF
+// output instead of random ones: an ephemeral key only ever encrypts one
+// message, so this is safe, and makes the ciphertext a function of the
+// ephemeral key and the message alone.
+
+use aes_gcm::{
+    aead::{consts::U16, AeadInPlace, KeyInit},
+    aes::Aes256,
+    AesGcm, Key, Nonce, Tag,
+};
+use hkdf::Hkdf;
+use libsecp256k1::{PublicKey, SecretKey};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use crate::common::error::{AkeError, Result};
+
+pub const PUBLIC_KEY_LENGTH: usize = 65;
+pub const NONCE_LENGTH: usize = 16;
+pub const TAG_LENGTH: usize = 16;
+const KEY_LENGTH: usize = 32;
+
+type Cipher = AesGcm<Aes256, U16>;
+
+// AES-256-GCM key and nonce shared by the holders of `sk` and `pk`
+fn dem(eph_pk: &PublicKey, sk: &SecretKey, pk: &PublicKey) -> Result<(Cipher, Vec<u8>)> {
+    let mut shared = *pk;
+    shared
+        .tweak_mul_assign(sk)
+        .map_err(|_| AkeError::Primitive("ECDH"))?;
+    let ikm = Zeroizing::new([eph_pk.serialize(), shared.serialize()].concat());
+
+    let mut okm = Zeroizing::new([0u8; KEY_LENGTH + NONCE_LENGTH]);
+    Hkdf::<Sha256>::new(None, ikm.as_slice())
+        .expand(&[], okm.as_mut_slice())
+        .map_err(|_| AkeError::Primitive("HKDF"))?;
+    let (key, nonce) = okm.split_at(KEY_LENGTH);
+
+    Ok((Cipher::new(Key::<Cipher>::from_slice(key)), nonce.to_vec()))
+}
+
+pub fn encrypt(pk: &PublicKey, m: &[u8], ephemeral: &(SecretKey, PublicKey)) -> Result<Vec<u8>> {
+    let (eph_sk, eph_pk) = ephemeral;
+    let (cipher, nonce) = dem(eph_pk, eph_sk, pk)?;
+
+    let mut ciphertext = m.to_vec();
+    let tag = cipher
+        .encrypt_in_place_detached(Nonce::from_slice(&nonce), &[], &mut ciphertext)
+        .map_err(|_| AkeError::Primitive("ECIES encryption"))?;
+
+    Ok([eph_pk.serialize().as_slice(), &nonce, &tag, &ciphertext].concat())
+}
+
+pub fn decrypt(sk: &SecretKey, ct: &[u8]) -> Result<Vec<u8>> {
+    if ct.len() < PUBLIC_KEY_LENGTH + NONCE_LENGTH + TAG_LENGTH {
+        return Err(AkeError::Decoding("ECIES ciphertext"));
+    }
+    let (eph_pk, rest) = ct.split_at(PUBLIC_KEY_LENGTH);
+    let (nonce, rest) = rest.split_at(NONCE_LENGTH);
+    let (tag, ciphertext) = rest.split_at(TAG_LENGTH);
+    let eph_pk = PublicKey::parse_slice(eph_pk, None)
+        .map_err(|_| AkeError::Decoding("ECIES ephemeral key"))?;
+
+    // As in ecies-rs the nonce is read from the ciphertext
+    let (cipher, _) = dem(&eph_pk, sk, &eph_pk)?;
+    let mut m = ciphertext.to_vec();
+    cipher
+        .decrypt_in_place_detached(Nonce::from_slice(nonce), &[], &mut m, Tag::from_slice(tag))
+        .map_err(|_| AkeError::Verification("ECIES ciphertext"))?;
+
+    Ok(m)
+}
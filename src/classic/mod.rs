@@ -2,7 +2,10 @@ pub mod ccapke;
 pub mod client;
 pub mod commitment;
 pub mod config;
+pub mod ecies;
 pub mod pke;
 pub mod protocol;
 pub mod server;
 pub mod sig;
+pub mod store;
+pub mod vectors;
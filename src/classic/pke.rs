@@ -1,33 +1,106 @@
-use pke_ecies::{decrypt, encrypt};
-use pke_ecies::{utils::generate_keypair, PublicKey, SecretKey};
+use libsecp256k1::{PublicKey, SecretKey};
+use rand::{CryptoRng, RngCore};
 use sha2::{Digest, Sha256};
 
-pub fn pke_gen() -> (PublicKey, SecretKey) {
-    let (sk, pk) = generate_keypair();
+use crate::common::{
+    error::{AkeError, Result},
+    secret::Wipe,
+    suite::Pke,
+    utils::{ct_eq, get_random_bytes},
+};
 
-    (pk, sk)
+use super::ecies::{decrypt, encrypt};
+
+impl Wipe for SecretKey {
+    fn wipe(&mut self) {
+        self.clear();
+    }
+}
+
+// Rejection sampling of 32-byte strings until one is a valid scalar
+pub fn secret_key_from_rng<R: RngCore + CryptoRng>(rng: &mut R) -> SecretKey {
+    loop {
+        if let Ok(sk) = SecretKey::parse_slice(&get_random_bytes(rng, 32)) {
+            return sk;
+        }
+    }
+}
+
+pub fn pke_gen<R: RngCore + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey) {
+    let sk = secret_key_from_rng(rng);
+
+    (PublicKey::from_secret_key(&sk), sk)
 }
 
-pub fn pke_enc(pk: &PublicKey, m: &[u8], r: &[u8]) -> Vec<u8> {
+pub fn pke_enc(pk: &PublicKey, m: &[u8], r: &[u8]) -> Result<Vec<u8>> {
     let mut hasher = Sha256::new();
     hasher.update(r);
     let nonce = hasher.finalize().to_vec();
-    let sk = SecretKey::parse_slice(&nonce[0..32]).unwrap();
-    let pk = &pk.serialize();
-    encrypt(pk, m, &(sk, PublicKey::from_secret_key(&sk))).unwrap()
+    let sk = SecretKey::parse_slice(&nonce[0..32])
+        .map_err(|_| AkeError::Primitive("ECIES ephemeral key derivation"))?;
+    encrypt(pk, m, &(sk, PublicKey::from_secret_key(&sk)))
 }
 
-pub fn pke_dec(sk: &SecretKey, ct: &[u8]) -> Vec<u8> {
-    let sk = &sk.serialize();
-    decrypt(sk, ct).unwrap()
+pub fn pke_dec(sk: &SecretKey, ct: &[u8]) -> Result<Vec<u8>> {
+    decrypt(sk, ct).map_err(|_| AkeError::Verification("PKE ciphertext"))
 }
 
 pub fn check_ciphertext(c1: &[u8], c2: &[u8]) -> bool {
-    let are_equal: bool = c1
-        .to_owned()
-        .iter()
-        .zip(c2.to_owned().iter())
-        .all(|(a, b)| a == b);
+    ct_eq(c1, c2)
+}
+
+pub struct Ecies;
+
+impl Pke for Ecies {
+    type PublicKey = PublicKey;
+    type SecretKey = SecretKey;
+    type Ciphertext = Vec<u8>;
+
+    fn algorithm_name(&self) -> String {
+        "ECIES-secp256k1".to_string()
+    }
+
+    fn pke_gen<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Result<(PublicKey, SecretKey)> {
+        Ok(pke_gen(rng))
+    }
+
+    fn pke_enc(&self, pk: &PublicKey, m: &[u8], r: &[u8]) -> Result<Vec<u8>> {
+        pke_enc(pk, m, r)
+    }
+
+    fn pke_dec(&self, sk: &SecretKey, ct: &Vec<u8>) -> Result<Vec<u8>> {
+        pke_dec(sk, ct)
+    }
+
+    fn check_ciphertext(&self, c1: &Vec<u8>, c2: &Vec<u8>) -> bool {
+        check_ciphertext(c1, c2)
+    }
+
+    fn pke_randomness<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Result<Vec<u8>> {
+        Ok(get_random_bytes(rng, 32))
+    }
+
+    fn public_key_to_bytes(&self, pk: &PublicKey) -> Vec<u8> {
+        pk.serialize().to_vec()
+    }
+
+    fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<PublicKey> {
+        PublicKey::parse_slice(bytes, None).map_err(|_| AkeError::Decoding("ECIES public key"))
+    }
+
+    fn secret_key_to_bytes(&self, sk: &SecretKey) -> Vec<u8> {
+        sk.serialize().to_vec()
+    }
+
+    fn secret_key_from_bytes(&self, bytes: &[u8]) -> Result<SecretKey> {
+        SecretKey::parse_slice(bytes).map_err(|_| AkeError::Decoding("ECIES secret key"))
+    }
+
+    fn ciphertext_to_bytes(&self, ct: &Vec<u8>) -> Vec<u8> {
+        ct.clone()
+    }
 
-    are_equal
+    fn ciphertext_from_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>> {
+        Ok(bytes.to_vec())
+    }
 }
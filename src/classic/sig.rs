@@ -1,23 +1,84 @@
-use aes_gcm::aead::rand_core;
 use k256::ecdsa::{signature::Signer, Signature, SigningKey, VerifyingKey};
 
 use k256::ecdsa::signature::Verifier;
 
-use rand_core::OsRng;
+use rand::{CryptoRng, RngCore};
 
-pub fn sig_gen() -> (VerifyingKey, SigningKey) {
-    let sk = SigningKey::random(&mut OsRng);
+use crate::common::{
+    error::{AkeError, Result},
+    secret::Wipe,
+    suite::Sig,
+};
+
+// k256 already zeroizes its secret keys on drop
+impl Wipe for k256::SecretKey {
+    fn wipe(&mut self) {}
+}
+
+pub fn sig_gen<R: RngCore + CryptoRng>(rng: &mut R) -> (VerifyingKey, SigningKey) {
+    let sk = SigningKey::random(rng);
     let pk = VerifyingKey::from(&sk);
 
     (pk, sk)
 }
 
-pub fn sig_sign(sk: &SigningKey, m: &[u8]) -> Signature {
-    let signature: Signature = sk.sign(m);
-
-    signature
+pub fn sig_sign(sk: &SigningKey, m: &[u8]) -> Result<Signature> {
+    sk.try_sign(m).map_err(|_| AkeError::Primitive("signing"))
 }
 
 pub fn sig_vry(pk: &VerifyingKey, m: &[u8], signature: &Signature) -> bool {
     pk.verify(m, signature).is_ok()
 }
+
+pub struct Ecdsa;
+
+impl Sig for Ecdsa {
+    type PublicKey = k256::PublicKey;
+    type SecretKey = k256::SecretKey;
+    type Signature = Signature;
+
+    fn algorithm_name(&self) -> String {
+        "ECDSA-secp256k1".to_string()
+    }
+
+    fn sig_gen<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(k256::PublicKey, k256::SecretKey)> {
+        let (pk, sk) = sig_gen(rng);
+
+        Ok((pk.into(), sk.into()))
+    }
+
+    fn sig_sign(&self, sk: &k256::SecretKey, m: &[u8]) -> Result<Signature> {
+        sig_sign(&sk.into(), m)
+    }
+
+    fn sig_vry(&self, pk: &k256::PublicKey, m: &[u8], signature: &Signature) -> bool {
+        sig_vry(&(*pk).into(), m, signature)
+    }
+
+    fn public_key_to_bytes(&self, pk: &k256::PublicKey) -> Vec<u8> {
+        pk.to_sec1_bytes().to_vec()
+    }
+
+    fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<k256::PublicKey> {
+        k256::PublicKey::from_sec1_bytes(bytes).map_err(|_| AkeError::Decoding("ECDSA public key"))
+    }
+
+    fn secret_key_to_bytes(&self, sk: &k256::SecretKey) -> Vec<u8> {
+        sk.to_bytes().to_vec()
+    }
+
+    fn secret_key_from_bytes(&self, bytes: &[u8]) -> Result<k256::SecretKey> {
+        k256::SecretKey::from_slice(bytes).map_err(|_| AkeError::Decoding("ECDSA secret key"))
+    }
+
+    fn signature_to_bytes(&self, signature: &Signature) -> Vec<u8> {
+        signature.to_vec()
+    }
+
+    fn signature_from_bytes(&self, bytes: &[u8]) -> Result<Signature> {
+        Signature::from_slice(bytes).map_err(|_| AkeError::Decoding("ECDSA signature"))
+    }
+}
@@ -0,0 +1,35 @@
+// Known-answer vectors of the classic suite, see `common::vectors`. Each vector
+// records its own number of clients and PRF, so one file can mix several.
+
+use super::config::Config;
+use crate::common::{
+    error::Result,
+    vectors::{check_vector, check_vectors, generate_vectors, vector_parameters, TestVector},
+};
+
+pub use crate::common::vectors::{read_vectors, write_vectors};
+
+// Shipped with the crate and checked by `cargo test`
+pub const VECTORS_FILE: &str = "vectors/classic.json";
+
+// Configuration a vector was generated with
+pub fn vector_config(vector: &TestVector) -> Result<Config> {
+    let (users, prf) = vector_parameters(vector)?;
+    let mut config = Config::new(users);
+    config.set_prf_algorithm(prf);
+
+    Ok(config)
+}
+
+pub fn check(vector: &TestVector) -> Result<()> {
+    check_vector(&vector_config(vector)?, vector)
+}
+
+pub fn generate(config: &Config, number: u64) -> Result<Vec<TestVector>> {
+    generate_vectors(config, number)
+}
+
+// Checks every vector and prints one line per seed. False if any fails.
+pub fn check_all(vectors: &[TestVector]) -> bool {
+    check_vectors(vectors, check)
+}
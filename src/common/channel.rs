@@ -0,0 +1,221 @@
+// Record layer over a completed session. Each side encrypts with its own
+// traffic key from the key schedule (AES-256-GCM) and every record is sent as
+// one `common::net` frame:
+//
+//   record := type (u8) || sequence number (u64) || AES-GCM(key, nonce, payload, aad)
+//
+// with nonce := 0 (u32) || sequence number and aad := type || sequence number.
+// Sequence numbers start at 0 in each direction and a record with any other
+// number than the next expected one is rejected, so replayed, reordered or
+// dropped records are detected. A close record ends each direction.
+
+use std::io::{Read, Write};
+
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+
+use super::{
+    client::Client,
+    error::{AkeError, Result},
+    net::{recv_frame, send_frame},
+    protocol::SessionHandle,
+    server::Server,
+    suite::Suite,
+};
+
+pub const RECORD_HEADER_LENGTH: usize = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum RecordType {
+    Data = 1,
+    Close = 2,
+}
+
+impl TryFrom<u8> for RecordType {
+    type Error = AkeError;
+
+    fn try_from(tag: u8) -> Result<Self> {
+        match tag {
+            1 => Ok(RecordType::Data),
+            2 => Ok(RecordType::Close),
+            _ => Err(AkeError::Decoding("record type")),
+        }
+    }
+}
+
+pub struct SecureChannel<T: Read + Write> {
+    stream: T,
+    sender: Aes256Gcm,
+    receiver: Aes256Gcm,
+    send_sequence: u64,
+    receive_sequence: u64,
+    // Set once a close record is sent or received
+    send_closed: bool,
+    receive_closed: bool,
+    // Set once sending a record fails
+    send_broken: bool,
+}
+
+fn cipher(key: &[u8]) -> Result<Aes256Gcm> {
+    Aes256Gcm::new_from_slice(key).map_err(|_| AkeError::Primitive("AES-GCM key setup"))
+}
+
+fn header(record_type: RecordType, sequence: u64) -> [u8; RECORD_HEADER_LENGTH] {
+    let mut header = [0u8; RECORD_HEADER_LENGTH];
+    header[0] = record_type as u8;
+    header[1..].copy_from_slice(&sequence.to_be_bytes());
+    header
+}
+
+fn nonce(sequence: u64) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[4..].copy_from_slice(&sequence.to_be_bytes());
+    nonce
+}
+
+impl<T: Read + Write> SecureChannel<T> {
+    fn new(stream: T, send_key: &[u8], receive_key: &[u8]) -> Result<Self> {
+        Ok(SecureChannel {
+            stream,
+            sender: cipher(send_key)?,
+            receiver: cipher(receive_key)?,
+            send_sequence: 0,
+            receive_sequence: 0,
+            send_closed: false,
+            receive_closed: false,
+            send_broken: false,
+        })
+    }
+
+    // Client side, once round 5 has derived the key schedule
+    pub fn client<S: Suite>(client: &Client<S>, stream: T) -> Result<Self> {
+        let schedule = client.get_key_schedule()?;
+
+        Self::new(stream, schedule.get_client_key(), schedule.get_server_key())
+    }
+
+    // Server side of session `handle`, once round 6 has derived the key schedule
+    pub fn server<S: Suite>(server: &Server<S>, handle: SessionHandle, stream: T) -> Result<Self> {
+        let schedule = server.get_key_schedule(handle)?;
+
+        Self::new(stream, schedule.get_server_key(), schedule.get_client_key())
+    }
+
+    fn send_record(&mut self, record_type: RecordType, payload: &[u8]) -> Result<()> {
+        if self.send_closed {
+            return Err(AkeError::ChannelClosed);
+        }
+        if self.send_broken {
+            return Err(AkeError::ChannelBroken);
+        }
+        let sequence = self.send_sequence;
+        // A sequence number, and so a nonce, is never used twice
+        let next_sequence = sequence
+            .checked_add(1)
+            .ok_or(AkeError::Primitive("record sequence number"))?;
+
+        let header = header(record_type, sequence);
+        let ciphertext = self
+            .sender
+            .encrypt(
+                Nonce::from_slice(&nonce(sequence)),
+                Payload {
+                    msg: payload,
+                    aad: &header,
+                },
+            )
+            .map_err(|_| AkeError::Primitive("AES-GCM encryption"))?;
+
+        // After a failed write the peer may or may not have the record, so no
+        // later record could be numbered consistently
+        if let Err(err) = send_frame(&mut self.stream, &[header.as_slice(), &ciphertext].concat()) {
+            self.send_broken = true;
+            return Err(err);
+        }
+        self.send_sequence = next_sequence;
+
+        Ok(())
+    }
+
+    pub fn send(&mut self, data: &[u8]) -> Result<()> {
+        self.send_record(RecordType::Data, data)
+    }
+
+    // Tells the peer that no more data follows. Receiving is still possible.
+    pub fn close(&mut self) -> Result<()> {
+        self.send_record(RecordType::Close, &[])?;
+        self.send_closed = true;
+
+        Ok(())
+    }
+
+    // Next data record, or None once the peer has closed its direction
+    pub fn recv(&mut self) -> Result<Option<Vec<u8>>> {
+        if self.receive_closed {
+            return Ok(None);
+        }
+
+        let record = recv_frame(&mut self.stream)?;
+        if record.len() < RECORD_HEADER_LENGTH {
+            return Err(AkeError::Decoding("record header"));
+        }
+        let (header, ciphertext) = record.split_at(RECORD_HEADER_LENGTH);
+        let record_type = RecordType::try_from(header[0])?;
+        let mut sequence = [0u8; 8];
+        sequence.copy_from_slice(&header[1..]);
+        let sequence = u64::from_be_bytes(sequence);
+
+        if sequence != self.receive_sequence {
+            return Err(AkeError::UnexpectedRecord {
+                expected: self.receive_sequence,
+                received: sequence,
+            });
+        }
+
+        let payload = self
+            .receiver
+            .decrypt(
+                Nonce::from_slice(&nonce(sequence)),
+                Payload {
+                    msg: ciphertext,
+                    aad: header,
+                },
+            )
+            .map_err(|_| AkeError::Verification("record"))?;
+        self.receive_sequence = sequence
+            .checked_add(1)
+            .ok_or(AkeError::Primitive("record sequence number"))?;
+
+        match record_type {
+            RecordType::Data => Ok(Some(payload)),
+            RecordType::Close => {
+                self.receive_closed = true;
+                Ok(None)
+            }
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.send_closed && self.receive_closed
+    }
+
+    // True once a send has failed. Nothing more can be sent on the channel.
+    pub fn is_broken(&self) -> bool {
+        self.send_broken
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.stream
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.stream
+    }
+
+    pub fn into_inner(self) -> T {
+        self.stream
+    }
+}
@@ -0,0 +1,341 @@
+use std::fmt;
+
+use super::{
+    error::{AkeError, Result},
+    protocol::{
+        M1Message, M2Message, M3Message, M4Message, M5Message, M6Message, M7Message, SessionHandle,
+    },
+    schedule::KeySchedule,
+    secret::Secret,
+    server::Server,
+    suite::{
+        CcaPkePublicKey, PkeCiphertext, PkePublicKey, PkeSecretKey, SigPublicKey, Signature, Suite,
+    },
+};
+
+// Commitment and its opening (r, x)
+pub type Commitment = (Vec<u8>, Secret<(Vec<u8>, Vec<u8>)>);
+
+// (c_1, ..., c_l, r, pk*, signature2)
+pub type M2Info<S> = (
+    Vec<PkeCiphertext<S>>,
+    Vec<u8>,
+    CcaPkePublicKey<S>,
+    Signature<S>,
+);
+
+pub struct Client<S: Suite> {
+    id: u32,
+    ek: Option<Secret<PkeSecretKey<S>>>,
+    ni: Secret<Vec<u8>>,
+    vks: Vec<PkePublicKey<S>>,
+    commitment: Commitment,
+    commitment_server: Commitment,
+    cis: Vec<PkeCiphertext<S>>,
+    r: Vec<u8>,
+    pk: Option<CcaPkePublicKey<S>>,
+    k: Option<Secret<Vec<u8>>>,
+    ns: Secret<Vec<u8>>,
+    signature2: Option<Signature<S>>,
+    signature4: Option<Signature<S>>,
+    pk_s: Option<SigPublicKey<S>>,
+    sid: Option<Secret<Vec<u8>>>,
+    // Running transcript hash th_i, see `common::transcript`
+    transcript: Vec<u8>,
+    // Set once round 5 derives k
+    schedule: Option<KeySchedule>,
+    // Server finished tag of m6, see rounds 7 to 9
+    server_finished: Option<Vec<u8>>,
+    confirmed: bool,
+    // Assigned by the server and learnt from m2
+    handle: Option<SessionHandle>,
+}
+
+impl<S: Suite> Client<S> {
+    pub fn new(id: u32) -> Self {
+        Client {
+            id,
+            ek: None,
+            ni: Secret::default(),
+            vks: Vec::new(),
+            commitment: Commitment::default(),
+            commitment_server: Commitment::default(),
+            cis: Vec::new(),
+            r: Vec::new(),
+            pk: None,
+            k: None,
+            ns: Secret::default(),
+            signature2: None,
+            signature4: None,
+            pk_s: None,
+            sid: None,
+            transcript: Vec::new(),
+            schedule: None,
+            server_finished: None,
+            confirmed: false,
+            handle: None,
+        }
+    }
+
+    pub fn set_ek(&mut self, ek: PkeSecretKey<S>) {
+        self.ek = Some(Secret::new(ek));
+    }
+
+    pub fn set_vks(&mut self, vks: Vec<PkePublicKey<S>>) {
+        self.vks = vks;
+    }
+
+    pub fn set_ni(&mut self, ni: &[u8]) {
+        self.ni = Secret::new(ni.to_owned());
+    }
+
+    pub fn set_ns(&mut self, ns: &[u8]) {
+        self.ns = Secret::new(ns.to_owned());
+    }
+
+    pub fn set_commitment(&mut self, commitment: (Vec<u8>, (Vec<u8>, Vec<u8>))) {
+        let (comm, open) = commitment;
+        self.commitment = (comm, Secret::new(open));
+    }
+
+    pub fn set_commitment_server(&mut self, commitment: (Vec<u8>, (Vec<u8>, Vec<u8>))) {
+        let (comm, open) = commitment;
+        self.commitment_server = (comm, Secret::new(open));
+    }
+
+    pub fn set_k(&mut self, k: Vec<u8>) {
+        self.k = Some(Secret::new(k));
+    }
+
+    pub fn set_sid(&mut self, sid: Vec<u8>) {
+        self.sid = Some(Secret::new(sid));
+    }
+
+    pub fn set_transcript(&mut self, th: Vec<u8>) {
+        self.transcript = th;
+    }
+
+    pub fn get_transcript(&self) -> &[u8] {
+        &self.transcript
+    }
+
+    pub fn set_key_schedule(&mut self, schedule: KeySchedule) {
+        self.schedule = Some(schedule);
+    }
+
+    pub fn get_key_schedule(&self) -> Result<&KeySchedule> {
+        self.schedule
+            .as_ref()
+            .ok_or(AkeError::MissingState("key schedule"))
+    }
+
+    pub fn export_keying_material(
+        &self,
+        config: &S,
+        label: &[u8],
+        context: &[u8],
+        length: usize,
+    ) -> Result<Vec<u8>> {
+        self.get_key_schedule()?
+            .export_keying_material(config, label, context, length)
+    }
+
+    pub fn get_ek(&self) -> Result<&PkeSecretKey<S>> {
+        self.ek
+            .as_ref()
+            .map(Secret::expose)
+            .ok_or(AkeError::MissingState("client decryption key ek"))
+    }
+
+    // Unset until round 5 succeeds
+    pub fn get_sid(&self) -> Result<&[u8]> {
+        self.sid
+            .as_ref()
+            .map(|sid| sid.expose().as_slice())
+            .ok_or(AkeError::MissingState("session identifier"))
+    }
+
+    pub fn get_signature4(&self) -> Result<Signature<S>> {
+        self.signature4
+            .clone()
+            .ok_or(AkeError::MissingState("signature of m4"))
+    }
+
+    pub fn get_key(&self) -> Result<&[u8]> {
+        self.k
+            .as_ref()
+            .map(|k| k.expose().as_slice())
+            .ok_or(AkeError::MissingState("session key"))
+    }
+
+    pub fn get_pk(&self) -> Result<CcaPkePublicKey<S>> {
+        self.pk
+            .clone()
+            .ok_or(AkeError::MissingState("CCA-PKE public key pk*"))
+    }
+
+    pub fn set_pk(&mut self, pk: CcaPkePublicKey<S>) {
+        self.pk = Some(pk);
+    }
+
+    pub fn set_pks(&mut self, pk_s: SigPublicKey<S>) {
+        self.pk_s = Some(pk_s);
+    }
+
+    pub fn get_pks(&self) -> Result<SigPublicKey<S>> {
+        self.pk_s
+            .clone()
+            .ok_or(AkeError::MissingState("server verification key"))
+    }
+
+    pub fn get_ni(&self) -> &[u8] {
+        self.ni.expose()
+    }
+
+    pub fn get_ns(&self) -> &[u8] {
+        self.ns.expose()
+    }
+
+    pub fn get_r(&self) -> Vec<u8> {
+        self.r.clone()
+    }
+
+    pub fn get_cis(&self) -> Vec<PkeCiphertext<S>> {
+        self.cis.clone()
+    }
+
+    pub fn get_commitment(&self) -> (&[u8], &(Vec<u8>, Vec<u8>)) {
+        (&self.commitment.0, self.commitment.1.expose())
+    }
+
+    pub fn get_commitment_server(&self) -> (&[u8], &(Vec<u8>, Vec<u8>)) {
+        (&self.commitment_server.0, self.commitment_server.1.expose())
+    }
+
+    pub fn get_vks(&self) -> Vec<PkePublicKey<S>> {
+        self.vks.clone()
+    }
+
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn get_handle(&self) -> Result<SessionHandle> {
+        self.handle.ok_or(AkeError::MissingState("session handle"))
+    }
+
+    // m1 carries no client identifier: the server answers with a handle
+    pub fn send_m1(&self, m1: M1Message, server: &mut Server<S>) -> SessionHandle {
+        server.receive_m1(m1)
+    }
+
+    pub fn send_m3(&self, m3: M3Message, server: &mut Server<S>) {
+        server.receive_m3(m3);
+    }
+
+    pub fn send_m5(&self, m5: M5Message<S>, server: &mut Server<S>) {
+        server.receive_m5(m5);
+    }
+
+    pub fn receive_m2(&mut self, m2: M2Message<S>) {
+        let ((cis, r, pk), signature2, handle) = m2;
+        self.handle = Some(handle);
+        self.cis = cis;
+        self.r = r;
+        self.pk = Some(pk);
+        self.signature2 = Some(signature2);
+    }
+
+    pub fn get_m2_info(&self) -> Result<M2Info<S>> {
+        let signature2 = self
+            .signature2
+            .clone()
+            .ok_or(AkeError::MissingState("signature of m2"))?;
+
+        Ok((self.cis.clone(), self.r.clone(), self.get_pk()?, signature2))
+    }
+
+    pub fn receive_m4(&mut self, m4: M4Message<S>) {
+        let (r, signature4) = m4;
+        self.r = r;
+        self.signature4 = Some(signature4);
+    }
+
+    pub fn send_m7(&self, m7: M7Message, server: &mut Server<S>) {
+        server.receive_m7(m7);
+    }
+
+    pub fn receive_m6(&mut self, m6: M6Message) {
+        self.server_finished = Some(m6);
+    }
+
+    pub fn get_server_finished(&self) -> Result<Vec<u8>> {
+        self.server_finished
+            .clone()
+            .ok_or(AkeError::MissingState("server finished tag"))
+    }
+
+    pub fn set_confirmed(&mut self) {
+        self.confirmed = true;
+    }
+
+    // True once round 8 has verified the server finished tag
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed
+    }
+}
+
+impl<S: Suite> Clone for Client<S> {
+    fn clone(&self) -> Client<S> {
+        Client {
+            id: self.id,
+            ek: self.ek.clone(),
+            ni: self.ni.clone(),
+            vks: self.vks.clone(),
+            commitment: self.commitment.clone(),
+            commitment_server: self.commitment_server.clone(),
+            cis: self.cis.clone(),
+            r: self.r.clone(),
+            pk: self.pk.clone(),
+            k: self.k.clone(),
+            ns: self.ns.clone(),
+            signature2: self.signature2.clone(),
+            signature4: self.signature4.clone(),
+            pk_s: self.pk_s.clone(),
+            sid: self.sid.clone(),
+            transcript: self.transcript.clone(),
+            schedule: self.schedule.clone(),
+            server_finished: self.server_finished.clone(),
+            confirmed: self.confirmed,
+            handle: self.handle,
+        }
+    }
+}
+
+// Written by hand so that `S` needs no `Debug`. Secrets print as `<redacted>`.
+impl<S: Suite> fmt::Debug for Client<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("id", &self.id)
+            .field("ek", &self.ek)
+            .field("ni", &self.ni)
+            .field("vks", &self.vks)
+            .field("commitment", &self.commitment)
+            .field("commitment_server", &self.commitment_server)
+            .field("cis", &self.cis)
+            .field("r", &self.r)
+            .field("pk", &self.pk)
+            .field("k", &self.k)
+            .field("ns", &self.ns)
+            .field("signature2", &self.signature2)
+            .field("signature4", &self.signature4)
+            .field("pk_s", &self.pk_s)
+            .field("sid", &self.sid)
+            .field("transcript", &self.transcript)
+            .field("schedule", &self.schedule)
+            .field("server_finished", &self.server_finished)
+            .field("confirmed", &self.confirmed)
+            .field("handle", &self.handle)
+            .finish()
+    }
+}
@@ -0,0 +1,65 @@
+use std::{fmt, io};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AkeError {
+    // A message, key or field could not be parsed
+    Decoding(&'static str),
+    // A cryptographic primitive (KEM, DEM, signature, PRF) failed
+    Primitive(&'static str),
+    // A round was run before the state it needs was set
+    MissingState(&'static str),
+    // A ciphertext failed to authenticate on decryption
+    Verification(&'static str),
+    // Reading or writing a socket or key file failed
+    Io(io::ErrorKind),
+    // The server signature on m2 does not verify under pk_S
+    BadSignature2,
+    // The server signature on m4 does not verify under pk_S
+    BadSignature4,
+    // c_j is not the encryption of n_S under vk_j with coins r_j
+    InconsistentCiphertext { j: u32 },
+    // comm_i or comm_S does not open to the values received in m5
+    BadCommitment,
+    // The finished tag of m6 or m7 does not match the transcript
+    BadConfirmation,
+    // A record arrived out of sequence: replayed, reordered or after a drop
+    UnexpectedRecord { expected: u64, received: u64 },
+    // Data was sent after `SecureChannel::close`
+    ChannelClosed,
+    // An earlier send on the `SecureChannel` failed
+    ChannelBroken,
+}
+
+impl fmt::Display for AkeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AkeError::Decoding(what) => write!(f, "could not decode {what}"),
+            AkeError::Primitive(what) => write!(f, "{what} failed"),
+            AkeError::MissingState(what) => write!(f, "missing {what}"),
+            AkeError::Verification(what) => write!(f, "{what} verification failed"),
+            AkeError::Io(kind) => write!(f, "I/O error: {kind}"),
+            AkeError::BadSignature2 => write!(f, "signature of m2 is invalid"),
+            AkeError::BadSignature4 => write!(f, "signature of m4 is invalid"),
+            AkeError::InconsistentCiphertext { j } => {
+                write!(f, "ciphertext c_{j} is inconsistent with n_S")
+            }
+            AkeError::BadCommitment => write!(f, "commitment does not open"),
+            AkeError::BadConfirmation => write!(f, "key confirmation tag is invalid"),
+            AkeError::UnexpectedRecord { expected, received } => {
+                write!(f, "expected record {expected}, received record {received}")
+            }
+            AkeError::ChannelClosed => write!(f, "channel is closed"),
+            AkeError::ChannelBroken => write!(f, "channel is broken by a failed send"),
+        }
+    }
+}
+
+impl std::error::Error for AkeError {}
+
+impl From<io::Error> for AkeError {
+    fn from(e: io::Error) -> Self {
+        AkeError::Io(e.kind())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, AkeError>;
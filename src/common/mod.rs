@@ -1,2 +1,15 @@
+pub mod channel;
+pub mod client;
+pub mod error;
+pub mod net;
 pub mod prf;
+pub mod protocol;
+pub mod schedule;
+pub mod secret;
+pub mod server;
+pub mod store;
+pub mod suite;
+pub mod transcript;
 pub mod utils;
+pub mod vectors;
+pub mod wire;
@@ -0,0 +1,228 @@
+// Runs the handshake over a byte stream such as a `TcpStream`. Every message
+// is sent as one frame: its length as a big-endian u32 followed by its
+// `common::wire` encoding. With `confirm` both sides also run the key
+// confirmation of rounds 7 to 9 and a session only succeeds once confirmed.
+
+use std::io::{self, Read, Write};
+use std::net::TcpListener;
+use std::time::Duration;
+
+use rand::{rngs::OsRng, CryptoRng, RngCore};
+
+use super::{
+    client::Client,
+    error::{AkeError, Result},
+    protocol::{
+        round_1, round_2, round_3, round_4, round_5, round_6, round_7, round_8, round_9,
+        SessionHandle,
+    },
+    server::Server,
+    suite::Suite,
+    utils::print_hex,
+    wire::{
+        decode_m1, decode_m2, decode_m3, decode_m4, decode_m5, decode_m6, decode_m7, encode_m1,
+        encode_m2, encode_m3, encode_m4, encode_m5, encode_m6, encode_m7,
+    },
+};
+
+// Large enough for m2 with many Classic McEliece ciphertexts
+pub const MAX_FRAME_LENGTH: usize = 1 << 26;
+// Read and write timeout of every connection accepted by `serve`
+pub const STREAM_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub fn send_frame<W: Write>(stream: &mut W, bytes: &[u8]) -> Result<()> {
+    if bytes.len() > MAX_FRAME_LENGTH {
+        return Err(AkeError::Decoding("frame length"));
+    }
+    stream.write_all(&(bytes.len() as u32).to_be_bytes())?;
+    stream.write_all(bytes)?;
+    stream.flush()?;
+
+    Ok(())
+}
+
+pub fn recv_frame<R: Read>(stream: &mut R) -> Result<Vec<u8>> {
+    let mut len = [0u8; 4];
+    stream.read_exact(&mut len)?;
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_FRAME_LENGTH {
+        return Err(AkeError::Decoding("frame length"));
+    }
+    // The buffer grows with the bytes that actually arrive, so a length alone
+    // does not make the receiver allocate it
+    let mut bytes = Vec::new();
+    stream.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(AkeError::Io(io::ErrorKind::UnexpectedEof));
+    }
+
+    Ok(bytes)
+}
+
+// Server side of one handshake (rounds 2, 4, 6, and 7 and 9 with `confirm`).
+// Returns the handle of the session, the server never learns which client it
+// talked to. A session that fails is aborted, one that succeeds is kept until
+// `Server::finish_session`.
+pub fn serve_session<S: Suite, T: Read + Write, R: RngCore + CryptoRng>(
+    server: &mut Server<S>,
+    config: &S,
+    stream: &mut T,
+    rng: &mut R,
+    confirm: bool,
+    verbose: bool,
+) -> Result<SessionHandle> {
+    let m1 = decode_m1(config, &recv_frame(stream)?)?;
+    let handle = server.receive_m1(m1);
+
+    match serve_rounds(server, config, stream, rng, handle, confirm, verbose) {
+        Ok(()) => Ok(handle),
+        Err(e) => {
+            server.abort_session(handle);
+            Err(e)
+        }
+    }
+}
+
+fn serve_rounds<S: Suite, T: Read + Write, R: RngCore + CryptoRng>(
+    server: &mut Server<S>,
+    config: &S,
+    stream: &mut T,
+    rng: &mut R,
+    handle: SessionHandle,
+    confirm: bool,
+    verbose: bool,
+) -> Result<()> {
+    let m2 = round_2(server, config, handle, rng)?;
+    send_frame(stream, &encode_m2(config, &m2))?;
+
+    let m3 = decode_m3(config, &recv_frame(stream)?)?;
+    if m3.1 != handle {
+        return Err(AkeError::Decoding("m3: session handle"));
+    }
+    server.receive_m3(m3);
+
+    let m4 = round_4(server, config, handle)?;
+    send_frame(stream, &encode_m4(config, &m4))?;
+
+    let m5 = decode_m5(config, &recv_frame(stream)?)?;
+    if m5.2 != handle {
+        return Err(AkeError::Decoding("m5: session handle"));
+    }
+    server.receive_m5(m5);
+
+    round_6(server, config, handle, verbose)?;
+
+    if confirm {
+        let m6 = round_7(server, config, handle)?;
+        send_frame(stream, &encode_m6(config, &m6))?;
+
+        let m7 = decode_m7(config, &recv_frame(stream)?)?;
+        if m7.1 != handle {
+            return Err(AkeError::Decoding("m7: session handle"));
+        }
+        server.receive_m7(m7);
+
+        round_9(server, config, handle, verbose)?;
+    }
+
+    Ok(())
+}
+
+// Client side of one handshake (rounds 1, 3, 5, and 8 with `confirm`)
+pub fn connect_session<S: Suite, T: Read + Write, R: RngCore + CryptoRng>(
+    client: &mut Client<S>,
+    config: &S,
+    stream: &mut T,
+    rng: &mut R,
+    confirm: bool,
+    verbose: bool,
+) -> Result<()> {
+    let m1 = round_1(client, config, rng)?;
+    send_frame(stream, &encode_m1(config, &m1))?;
+
+    let m2 = decode_m2(config, &recv_frame(stream)?)?;
+    client.receive_m2(m2);
+
+    let m3 = round_3(client, config, rng, verbose)?;
+    send_frame(stream, &encode_m3(config, &m3))?;
+
+    let m4 = decode_m4(config, &recv_frame(stream)?)?;
+    client.receive_m4(m4);
+
+    let m5 = round_5(client, config, rng, verbose)?;
+    send_frame(stream, &encode_m5(config, &m5))?;
+
+    if confirm {
+        let m6 = decode_m6(config, &recv_frame(stream)?)?;
+        client.receive_m6(m6);
+
+        let m7 = round_8(client, config, verbose)?;
+        send_frame(stream, &encode_m7(config, &m7))?;
+    }
+
+    Ok(())
+}
+
+// Accepts clients one at a time and prints the session key and sid of every
+// completed handshake before finishing its session. A failed connection or
+// handshake is reported and the server goes on. Each connection gets
+// `STREAM_TIMEOUT` to read and write, so an idle client cannot hold up the
+// others.
+pub fn serve<S: Suite>(
+    server: &mut Server<S>,
+    config: &S,
+    listener: &TcpListener,
+    confirm: bool,
+    verbose: bool,
+) -> Result<()> {
+    serve_with_timeout(server, config, listener, STREAM_TIMEOUT, confirm, verbose)
+}
+
+pub fn serve_with_timeout<S: Suite>(
+    server: &mut Server<S>,
+    config: &S,
+    listener: &TcpListener,
+    timeout: Duration,
+    confirm: bool,
+    verbose: bool,
+) -> Result<()> {
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                println!("[!] Accepting a connection failed: {e}");
+                continue;
+            }
+        };
+        let peer = match stream.peer_addr() {
+            Ok(peer) => peer,
+            Err(e) => {
+                println!("[!] Connection closed before the handshake: {e}");
+                continue;
+            }
+        };
+        if let Err(e) = stream
+            .set_read_timeout(Some(timeout))
+            .and_then(|_| stream.set_write_timeout(Some(timeout)))
+        {
+            println!("[!] Setting timeouts for {peer} failed: {e}");
+            continue;
+        }
+        if verbose {
+            println!("[S] Connection from {peer}");
+        }
+
+        match serve_session(server, config, &mut stream, &mut OsRng, confirm, verbose) {
+            Ok(handle) => {
+                println!("[!] Printing session key and identifier...");
+                print_hex(server.get_key(handle)?, "[S] key");
+                print_hex(server.get_sid(handle)?, "[S] sid");
+                println!();
+                server.finish_session(handle)?;
+            }
+            Err(e) => println!("[!] Handshake with {peer} failed: {e}"),
+        }
+    }
+
+    Ok(())
+}
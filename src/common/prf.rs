@@ -1,18 +1,205 @@
+// PRF_key(label, index) with an output of any length. `label` separates the
+// uses of the PRF (e.g. the coins of round 2) and `index` the outputs within
+// one use (e.g. the recipient j). Inputs are encoded as
+//
+//   enc(x) := len(x) (u32) || x        index := u64
+//
+// and the constructions are
+//
+//   AES-CTR:  AES-256-CTR with key SHA3-256(enc(label) || key) and initial
+//             counter block index || 0^8
+//   SHAKE256: SHAKE256(enc(label) || enc(key) || index)
+//   KMAC256:  KMAC256(key, index, 8 * length, label)
+
 use aes::cipher::{KeyIvInit, StreamCipher};
 use ctr;
+use sha3::{
+    digest::{ExtendableOutput, Update, XofReader},
+    CShake256, CShake256Core, Digest, Sha3_256, Shake256,
+};
+
+use super::{
+    error::{AkeError, Result},
+    suite::Prf,
+    wire::put_field,
+};
 
 type Aes256Ctr64BE = ctr::Ctr64BE<aes::Aes256>;
 
-pub fn prf(key: &[u8], nonce: &[u8]) -> Vec<u8> {
-    let plaintext = vec![0; 16];
-    let mut counter = [0; 16];
-    counter.copy_from_slice(nonce);
+pub static SUPPORTED_PRFS: &[&str] = &["AES-CTR", "SHAKE256", "KMAC256"];
+
+pub fn prf(key: &[u8], nonce: &[u8]) -> Result<Vec<u8>> {
+    prf_with_length(key, nonce, 16)
+}
+
+// First `length` bytes of the AES-256-CTR keystream. Only the last 8 bytes of
+// the nonce are used as block counter, so nonces that differ in the first 8
+// bytes never share keystream blocks.
+pub fn prf_with_length(key: &[u8], nonce: &[u8], length: usize) -> Result<Vec<u8>> {
+    if key.len() < 32 || nonce.len() != 16 {
+        return Err(AkeError::Primitive("PRF"));
+    }
     let mut k = [0_u8; 32];
     k.copy_from_slice(&key[0..32]);
 
-    let mut buf = plaintext.to_vec();
+    let mut buf = vec![0; length];
     let mut cipher = Aes256Ctr64BE::new(&k.into(), nonce.into());
     cipher.apply_keystream(&mut buf);
 
-    buf
+    Ok(buf)
+}
+
+pub fn print_supported_prfs() {
+    for prf in SUPPORTED_PRFS {
+        println!("[!] * {prf}");
+    }
+}
+
+fn check_key(key: &[u8]) -> Result<()> {
+    if key.is_empty() {
+        return Err(AkeError::Primitive("PRF key"));
+    }
+
+    Ok(())
+}
+
+// NIST SP 800-185, section 2.3
+fn left_encode(x: u64) -> Vec<u8> {
+    let bytes = x.to_be_bytes();
+    let skip = bytes.iter().take_while(|&&b| b == 0).count().min(7);
+    let mut out = vec![(8 - skip) as u8];
+    out.extend_from_slice(&bytes[skip..]);
+    out
+}
+
+fn right_encode(x: u64) -> Vec<u8> {
+    let bytes = x.to_be_bytes();
+    let skip = bytes.iter().take_while(|&&b| b == 0).count().min(7);
+    let mut out = bytes[skip..].to_vec();
+    out.push((8 - skip) as u8);
+    out
+}
+
+fn encode_string(x: &[u8]) -> Vec<u8> {
+    [left_encode(8 * x.len() as u64), x.to_vec()].concat()
+}
+
+fn bytepad(x: &[u8], w: usize) -> Vec<u8> {
+    let mut out = [left_encode(w as u64), x.to_vec()].concat();
+    out.resize(out.len().div_ceil(w) * w, 0);
+    out
+}
+
+// KMAC256(K, X, L, S) with L = 8 * length
+pub fn kmac256(key: &[u8], data: &[u8], length: usize, customization: &[u8]) -> Vec<u8> {
+    let mut hasher = CShake256::from_core(CShake256Core::new_with_function_name(
+        b"KMAC",
+        customization,
+    ));
+    hasher.update(&bytepad(&encode_string(key), 136));
+    hasher.update(data);
+    hasher.update(&right_encode(8 * length as u64));
+
+    let mut out = vec![0; length];
+    hasher.finalize_xof().read(&mut out);
+    out
+}
+
+pub struct AesCtrPrf;
+
+impl Prf for AesCtrPrf {
+    fn algorithm_name(&self) -> String {
+        "AES-CTR".to_string()
+    }
+
+    fn prf(&self, key: &[u8], label: &[u8], index: u64, length: usize) -> Result<Vec<u8>> {
+        check_key(key)?;
+        let mut input = Vec::new();
+        put_field(&mut input, label);
+        input.extend_from_slice(key);
+        let k = Sha3_256::digest(&input);
+
+        let mut nonce = [0u8; 16];
+        nonce[..8].copy_from_slice(&index.to_be_bytes());
+
+        prf_with_length(&k, &nonce, length)
+    }
+}
+
+pub struct Shake256Prf;
+
+impl Prf for Shake256Prf {
+    fn algorithm_name(&self) -> String {
+        "SHAKE256".to_string()
+    }
+
+    fn prf(&self, key: &[u8], label: &[u8], index: u64, length: usize) -> Result<Vec<u8>> {
+        check_key(key)?;
+        let mut input = Vec::new();
+        put_field(&mut input, label);
+        put_field(&mut input, key);
+        input.extend_from_slice(&index.to_be_bytes());
+
+        let mut hasher = Shake256::default();
+        hasher.update(&input);
+        let mut out = vec![0; length];
+        hasher.finalize_xof().read(&mut out);
+
+        Ok(out)
+    }
+}
+
+pub struct Kmac256Prf;
+
+impl Prf for Kmac256Prf {
+    fn algorithm_name(&self) -> String {
+        "KMAC256".to_string()
+    }
+
+    fn prf(&self, key: &[u8], label: &[u8], index: u64, length: usize) -> Result<Vec<u8>> {
+        check_key(key)?;
+
+        Ok(kmac256(key, &index.to_be_bytes(), length, label))
+    }
+}
+
+// Construction picked at run time, e.g. from the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrfAlgorithm {
+    AesCtr,
+    Shake256,
+    Kmac256,
+}
+
+impl PrfAlgorithm {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "AES-CTR" => Some(PrfAlgorithm::AesCtr),
+            "SHAKE256" => Some(PrfAlgorithm::Shake256),
+            "KMAC256" => Some(PrfAlgorithm::Kmac256),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PrfAlgorithm::AesCtr => "AES-CTR",
+            PrfAlgorithm::Shake256 => "SHAKE256",
+            PrfAlgorithm::Kmac256 => "KMAC256",
+        }
+    }
+}
+
+impl Prf for PrfAlgorithm {
+    fn algorithm_name(&self) -> String {
+        self.name().to_string()
+    }
+
+    fn prf(&self, key: &[u8], label: &[u8], index: u64, length: usize) -> Result<Vec<u8>> {
+        match self {
+            PrfAlgorithm::AesCtr => AesCtrPrf.prf(key, label, index, length),
+            PrfAlgorithm::Shake256 => Shake256Prf.prf(key, label, index, length),
+            PrfAlgorithm::Kmac256 => Kmac256Prf.prf(key, label, index, length),
+        }
+    }
 }
@@ -0,0 +1,554 @@
+use std::time::Duration;
+
+use rand::{CryptoRng, RngCore};
+use zeroize::Zeroizing;
+
+use crate::common::utils::{ct_eq, get_random_bytes};
+
+use super::{
+    client::Client,
+    error::{AkeError, Result},
+    schedule::{finished_tag, KeySchedule, CLIENT_FINISHED_LABEL, SERVER_FINISHED_LABEL},
+    secret::Secret,
+    server::Server,
+    suite::{
+        CcaPke, CcaPkeCiphertext, CcaPkePublicKey, Comm, Pke, PkeCiphertext, PkePublicKey,
+        PkeSecretKey, Sig, SigPublicKey, Signature, Suite,
+    },
+    transcript::{
+        derive_session_secret, transcript_start, transcript_update, SESSION_KEY_LABEL, SID_LABEL,
+    },
+    wire,
+};
+
+// Random identifier the server assigns to a handshake when it receives m1.
+// Client indices never leave the client.
+pub type SessionHandle = u64;
+
+pub type M1Message = Vec<u8>;
+pub type M2Message<S> = (
+    (Vec<PkeCiphertext<S>>, Vec<u8>, CcaPkePublicKey<S>),
+    Signature<S>,
+    SessionHandle,
+);
+pub type M3Message = (Vec<u8>, SessionHandle);
+pub type M4Message<S> = (Vec<u8>, Signature<S>);
+pub type M5Message<S> = (CcaPkeCiphertext<S>, (Vec<u8>, Vec<u8>), SessionHandle);
+// Finished tags of the optional key confirmation
+pub type M6Message = Vec<u8>;
+pub type M7Message = (Vec<u8>, SessionHandle);
+
+// m := (c_1, ..., c_l, r, pk*)
+fn m2_to_bytes<S: Suite>(
+    config: &S,
+    cis: &[PkeCiphertext<S>],
+    r: &[u8],
+    pk: &CcaPkePublicKey<S>,
+) -> Vec<u8> {
+    [
+        cis.iter()
+            .flat_map(|x| config.pke().ciphertext_to_bytes(x))
+            .collect(),
+        r.to_vec(),
+        config.ccapke().public_key_to_bytes(pk),
+    ]
+    .concat()
+}
+
+// Run by each client: the key pair is generated locally and only vk is
+// submitted to the server
+pub fn client_keygen<S: Suite, R: RngCore + CryptoRng>(
+    config: &S,
+    rng: &mut R,
+) -> Result<(PkePublicKey<S>, PkeSecretKey<S>)> {
+    config.pke().pke_gen(rng)
+}
+
+// Registers `config.get_users_number()` independent clients. Client j knows
+// only its own index and ek_j, plus the public vk_1, ..., vk_l and pk_S.
+pub fn registration<S: Suite, R: RngCore + CryptoRng>(
+    config: &S,
+    rng: &mut R,
+) -> Result<(Server<S>, Vec<Client<S>>)> {
+    let users = config.get_users_number();
+    let mut server: Server<S> = Server::new(config, rng)?;
+    let mut clients: Vec<Client<S>> = Vec::new();
+
+    for _ in 0..users {
+        let (vk, ek) = client_keygen(config, rng)?;
+        let id = server.add_key(vk);
+        let mut client = Client::new(id);
+        client.set_ek(ek);
+        clients.push(client);
+    }
+
+    for client in clients.iter_mut() {
+        client.set_pks(server.get_sig_pk());
+        client.set_vks(server.get_clients_keys());
+    }
+
+    Ok((server, clients))
+}
+
+pub fn round_1<S: Suite, R: RngCore + CryptoRng>(
+    client: &mut Client<S>,
+    config: &S,
+    rng: &mut R,
+) -> Result<M1Message> {
+    let ni = Zeroizing::new(get_random_bytes(rng, 32));
+    client.set_ni(&ni);
+    let (comm, open) = config.comm().comm(&ni, rng);
+    client.set_commitment((comm.clone(), open));
+
+    let th = transcript_start(config, &client.get_pks()?);
+    client.set_transcript(transcript_update(
+        config,
+        &th,
+        &wire::encode_m1(config, &comm),
+    ));
+
+    Ok(comm)
+}
+
+pub fn round_2<S: Suite, R: RngCore + CryptoRng>(
+    server: &mut Server<S>,
+    config: &S,
+    handle: SessionHandle,
+    rng: &mut R,
+) -> Result<M2Message<S>> {
+    if !server.has_session(handle) {
+        return Err(AkeError::MissingState("session for this handle"));
+    }
+    let (pk, sk) = config.ccapke().ccapke_gen(rng)?;
+    server.set_ccapke_keypair((pk.clone(), sk), handle)?;
+    let users = config.get_users_number();
+    let n_s = Zeroizing::new(get_random_bytes(rng, 32));
+    server.set_ns(handle, &n_s)?;
+    let r: Vec<u8> = config.pke().pke_randomness(rng)?;
+    let client_keys: Vec<PkePublicKey<S>> = server.get_clients_keys();
+    let mut cis: Vec<PkeCiphertext<S>> = Vec::new();
+
+    for i in 0..users {
+        let vk = client_keys
+            .get(i as usize)
+            .ok_or(AkeError::MissingState("public key of a registered client"))?;
+        let ri = config.pke_coins(&r, i)?;
+        let c = config.pke().pke_enc(vk, &n_s, &ri)?;
+        cis.push(c);
+    }
+    server.add_ciphertexts(handle, &cis, &r)?;
+
+    let to_sign: Vec<u8> = m2_to_bytes(config, &cis, &r, &pk);
+
+    let (_, sk_s) = server.get_sig_keypair();
+    let signature2: Signature<S> = config.sig().sig_sign(sk_s, &to_sign)?;
+
+    let m2 = ((cis, r, pk), signature2, handle);
+
+    let th = transcript_start(config, server.get_sig_keypair().0);
+    let th = transcript_update(
+        config,
+        &th,
+        &wire::encode_m1(config, &server.get_comm(handle)?),
+    );
+    let th = transcript_update(config, &th, &wire::encode_m2(config, &m2));
+    server.set_transcript(handle, th)?;
+
+    Ok(m2)
+}
+
+pub fn round_3<S: Suite, R: RngCore + CryptoRng>(
+    client: &mut Client<S>,
+    config: &S,
+    rng: &mut R,
+    verbose: bool,
+) -> Result<M3Message> {
+    let (cis, r, pk, signature2) = client.get_m2_info()?;
+    let handle = client.get_handle()?;
+    let id = client.get_id();
+    let pk_s: SigPublicKey<S> = client.get_pks()?;
+    client.set_pk(pk.clone());
+
+    let to_verify: Vec<u8> = m2_to_bytes(config, &cis, &r, &pk);
+
+    let verification = config.sig().sig_vry(&pk_s, &to_verify, &signature2);
+    if !verification {
+        if verbose {
+            println!("[C] Signature verification -> KO");
+        }
+        return Err(AkeError::BadSignature2);
+    }
+    if verbose {
+        println!("[C] Signature verification -> OK");
+    }
+
+    let ci = cis
+        .get(id as usize)
+        .ok_or(AkeError::Decoding("m2: no ciphertext for this client"))?;
+    let m2 = wire::encode_m2(config, &((cis.clone(), r, pk), signature2, handle));
+    let eki: &PkeSecretKey<S> = client.get_ek()?;
+
+    let ns = Zeroizing::new(config.pke().pke_dec(eki, ci)?);
+
+    client.set_ns(&ns);
+
+    let (comm_s, open_s) = config.comm().comm(&ns, rng);
+    client.set_commitment_server((comm_s.clone(), open_s));
+    let m3 = (comm_s, handle);
+
+    let th = transcript_update(config, client.get_transcript(), &m2);
+    let th = transcript_update(config, &th, &wire::encode_m3(config, &m3));
+    client.set_transcript(th);
+
+    Ok(m3)
+}
+
+pub fn round_4<S: Suite>(
+    server: &mut Server<S>,
+    config: &S,
+    handle: SessionHandle,
+) -> Result<M4Message<S>> {
+    // r is only revealed once the client has committed to n_S
+    let comm_s = server.get_comm_server(handle)?;
+    let r = server.get_r(handle)?;
+    let (_, sk_s) = server.get_sig_keypair();
+
+    let signature4: Signature<S> = config.sig().sig_sign(sk_s, &r)?;
+    let m4 = (r, signature4);
+
+    let th = server.get_transcript(handle)?;
+    let th = transcript_update(config, th, &wire::encode_m3(config, &(comm_s, handle)));
+    let th = transcript_update(config, &th, &wire::encode_m4(config, &m4));
+    server.set_transcript(handle, th)?;
+
+    Ok(m4)
+}
+
+pub fn round_5<S: Suite, R: RngCore + CryptoRng>(
+    client: &mut Client<S>,
+    config: &S,
+    rng: &mut R,
+    verbose: bool,
+) -> Result<M5Message<S>> {
+    let users = config.get_users_number();
+    let cis = client.get_cis();
+    let vks: Vec<PkePublicKey<S>> = client.get_vks();
+    let r: Vec<u8> = client.get_r();
+    let ni = Zeroizing::new(client.get_ni().to_vec());
+    let ns = Zeroizing::new(client.get_ns().to_vec());
+    let pk_s: SigPublicKey<S> = client.get_pks()?;
+    let signature4 = client.get_signature4()?;
+
+    let verification = config.sig().sig_vry(&pk_s, &r, &signature4);
+    if !verification {
+        if verbose {
+            println!("[C] Signature verification -> KO");
+        }
+        return Err(AkeError::BadSignature4);
+    }
+    if verbose {
+        println!("[C] Signature verification -> OK");
+    }
+
+    let pk = client.get_pk()?;
+
+    for j in 0..users {
+        let vkj = vks
+            .get(j as usize)
+            .ok_or(AkeError::MissingState("public key of a registered client"))?;
+        let rj = config.pke_coins(&r, j)?;
+        let cj = cis
+            .get(j as usize)
+            .ok_or(AkeError::Decoding("m2: missing ciphertext"))?;
+
+        let cj_check = config.pke().pke_enc(vkj, &ns, &rj)?;
+
+        if !config.pke().check_ciphertext(&cj_check, cj) {
+            if verbose {
+                println!("[C] Ciphertext verification for j={j} -> KO");
+            }
+            return Err(AkeError::InconsistentCiphertext { j });
+        }
+        if verbose {
+            println!("[C] Ciphertext verification for j={j} -> OK");
+        }
+    }
+
+    let (_, (r_i, x_i)) = client.get_commitment();
+    let open_i = Zeroizing::new([r_i.as_slice(), x_i].concat());
+    let (_, open_s) = client.get_commitment_server();
+    let open_s = open_s.clone();
+
+    let ctxi = config.ccapke().ccapke_enc(&pk, &open_i, rng)?;
+    let m5 = (ctxi, open_s, client.get_handle()?);
+
+    let th = transcript_update(
+        config,
+        client.get_transcript(),
+        &wire::encode_m4(config, &(r, signature4)),
+    );
+    let th = transcript_update(config, &th, &wire::encode_m5(config, &m5));
+    let k = derive_session_secret(config, SESSION_KEY_LABEL, &ns, &ni, &th);
+    client.set_key_schedule(KeySchedule::derive(config, &k, &th)?);
+    client.set_k(k);
+    client.set_sid(derive_session_secret(config, SID_LABEL, &ns, &ni, &th));
+    client.set_transcript(th);
+
+    Ok(m5)
+}
+
+// A session that fails to verify what it received is dropped. One that only
+// misses a message is kept, the round can run again once it arrives.
+fn abort_on_failure<S: Suite>(
+    server: &mut Server<S>,
+    handle: SessionHandle,
+    result: Result<()>,
+) -> Result<()> {
+    if let Err(e) = &result {
+        if !matches!(e, AkeError::MissingState(_)) {
+            server.abort_session(handle);
+        }
+    }
+
+    result
+}
+
+// Derives k and sid, then wipes the handshake state of the session
+pub fn round_6<S: Suite>(
+    server: &mut Server<S>,
+    config: &S,
+    handle: SessionHandle,
+    verbose: bool,
+) -> Result<()> {
+    let result = derive_server_key(server, config, handle, verbose);
+    abort_on_failure(server, handle, result)
+}
+
+fn derive_server_key<S: Suite>(
+    server: &mut Server<S>,
+    config: &S,
+    handle: SessionHandle,
+    verbose: bool,
+) -> Result<()> {
+    let (_, sk) = server.get_ccapke_keypair(handle)?;
+    let ctxi = server.get_ctxi(handle)?;
+
+    let open_i_concat = Zeroizing::new(config.ccapke().ccapke_dec(sk, &ctxi)?);
+    if open_i_concat.len() < 32 {
+        return Err(AkeError::Decoding("m5: opening of comm_i"));
+    }
+    let open_i = Secret::new((open_i_concat[0..32].to_vec(), open_i_concat[32..].to_vec()));
+    let (ni, _) = open_i.expose();
+    let comm_i = server.get_comm(handle)?;
+    let comm_s = server.get_comm_server(handle)?;
+    let open_s = server.get_open_server(handle)?;
+
+    let ns = server.get_ns(handle)?;
+
+    let verification1 = config.comm().comm_vfy(&comm_i, open_i.expose());
+    let verification2 = config.comm().comm_vfy(&comm_s, open_s);
+
+    if !(verification1 && verification2) {
+        if verbose {
+            println!("[S] Commitment verification -> KO");
+        }
+        return Err(AkeError::BadCommitment);
+    }
+    if verbose {
+        println!("[S] Commitment verification -> OK");
+    }
+
+    let m5 = wire::encode_m5(config, &(ctxi, open_s.clone(), handle));
+    let th = transcript_update(config, server.get_transcript(handle)?, &m5);
+    let k = derive_session_secret(config, SESSION_KEY_LABEL, ns, ni, &th);
+    let sid = derive_session_secret(config, SID_LABEL, ns, ni, &th);
+
+    server.set_key_schedule(handle, KeySchedule::derive(config, &k, &th)?)?;
+    server.set_k(k, handle)?;
+    server.set_sid(sid, handle)?;
+    server.set_transcript(handle, th)?;
+
+    server.end_handshake(handle)
+}
+
+// Lengths of the encoded messages, header included
+pub fn get_m1_length<S: Suite>(config: &S, m1: &M1Message) -> usize {
+    wire::encode_m1(config, m1).len()
+}
+
+pub fn get_m2_length<S: Suite>(config: &S, m2: &M2Message<S>) -> usize {
+    wire::encode_m2(config, m2).len()
+}
+
+pub fn get_m3_length<S: Suite>(config: &S, m3: &M3Message) -> usize {
+    wire::encode_m3(config, m3).len()
+}
+
+pub fn get_m4_length<S: Suite>(config: &S, m4: &M4Message<S>) -> usize {
+    wire::encode_m4(config, m4).len()
+}
+
+// Optional key confirmation, run after round 6. The server proves it derived
+// the same key schedule over the same transcript.
+pub fn round_7<S: Suite>(
+    server: &mut Server<S>,
+    config: &S,
+    handle: SessionHandle,
+) -> Result<M6Message> {
+    let th = server.get_transcript(handle)?;
+    let key = server
+        .get_key_schedule(handle)?
+        .get_server_confirmation_key();
+    let m6 = finished_tag(config, key, SERVER_FINISHED_LABEL, th);
+
+    let th = transcript_update(config, th, &wire::encode_m6(config, &m6));
+    server.set_transcript(handle, th)?;
+
+    Ok(m6)
+}
+
+// The client checks the server finished tag and answers with its own. Sending
+// m7 is optional: without it only the client side is confirmed.
+pub fn round_8<S: Suite>(client: &mut Client<S>, config: &S, verbose: bool) -> Result<M7Message> {
+    let m6 = client.get_server_finished()?;
+    let schedule = client.get_key_schedule()?;
+    let th = client.get_transcript();
+
+    let expected = finished_tag(
+        config,
+        schedule.get_server_confirmation_key(),
+        SERVER_FINISHED_LABEL,
+        th,
+    );
+    if !ct_eq(&expected, &m6) {
+        if verbose {
+            println!("[C] Key confirmation -> KO");
+        }
+        return Err(AkeError::BadConfirmation);
+    }
+    if verbose {
+        println!("[C] Key confirmation -> OK");
+    }
+
+    let th = transcript_update(config, th, &wire::encode_m6(config, &m6));
+    let tag = finished_tag(
+        config,
+        schedule.get_client_confirmation_key(),
+        CLIENT_FINISHED_LABEL,
+        &th,
+    );
+    client.set_transcript(th);
+    client.set_confirmed();
+
+    Ok((tag, client.get_handle()?))
+}
+
+pub fn round_9<S: Suite>(
+    server: &mut Server<S>,
+    config: &S,
+    handle: SessionHandle,
+    verbose: bool,
+) -> Result<()> {
+    let result = check_client_finished(server, config, handle, verbose);
+    abort_on_failure(server, handle, result)
+}
+
+fn check_client_finished<S: Suite>(
+    server: &mut Server<S>,
+    config: &S,
+    handle: SessionHandle,
+    verbose: bool,
+) -> Result<()> {
+    let tag = server.get_client_finished(handle)?;
+    let key = server
+        .get_key_schedule(handle)?
+        .get_client_confirmation_key();
+    let expected = finished_tag(
+        config,
+        key,
+        CLIENT_FINISHED_LABEL,
+        server.get_transcript(handle)?,
+    );
+
+    if !ct_eq(&expected, &tag) {
+        if verbose {
+            println!("[S] Key confirmation -> KO");
+        }
+        return Err(AkeError::BadConfirmation);
+    }
+    if verbose {
+        println!("[S] Key confirmation -> OK");
+    }
+
+    server.set_confirmed(handle)
+}
+
+pub fn get_m5_length<S: Suite>(config: &S, m5: &M5Message<S>) -> usize {
+    wire::encode_m5(config, m5).len()
+}
+
+// Timings and message sizes of a local run, headed by the algorithms in use so
+// runs with different schemes can be compared
+pub fn show_diagram<S: Suite>(config: &S, times: &[Duration], lengths: &[usize]) {
+    let diagram = format!(
+        r#"
+PKE:       {pke}
+CCA-PKE:   {ccapke}
+Signature: {sig}
+
+                 Client i                     Server
+                    |                            |
+                    |                            | <---    Registration
+                    |                            |         for {clients} clients
+                    |                            |         ({registration:0>3} ms)
+Round 1        ---> |                            |
+({round1:0>8} µs)       |                            |
+                    |                            |
+                    |-------------m1------------>|
+                    |        ({m1:0>7} B)         |
+                    |                            | <---    Round 2
+                    |                            |         ({round2:0>8} ms)
+                    |                            |
+                    |<------------m2-------------|
+                    |        ({m2:0>7} B)         |
+Round 3        ---> |                            |
+({round3:0>8} ms)       |                            |
+                    |                            |
+                    |-------------m3------------>|
+                    |        ({m3:0>7} B)         |
+                    |                            | <---    Round 4
+                    |                            |         ({round4:0>8} ms)
+                    |                            |
+                    |<------------m4-------------|
+                    |        ({m4:0>7} B)         |
+Round 5        ---> |                            |
+({round5:0>8} ms)       |                            |
+                    |                            |
+                    |-------------m5------------>|
+                    |        ({m5:0>7} B)         |
+                    |                            | <---    Round 6
+                    |                            |         ({round6:0>8} µs)
+                    |                            |
+
+Total sent: {total} B
+"#,
+        pke = config.pke().algorithm_name(),
+        ccapke = config.ccapke().algorithm_name(),
+        sig = config.sig().algorithm_name(),
+        clients = config.get_users_number(),
+        registration = times[0].as_millis(),
+        round1 = times[1].as_micros(),
+        round2 = times[2].as_millis(),
+        round3 = times[3].as_millis(),
+        round4 = times[4].as_millis(),
+        round5 = times[5].as_millis(),
+        round6 = times[6].as_micros(),
+        m1 = lengths[0],
+        m2 = lengths[1],
+        m3 = lengths[2],
+        m4 = lengths[3],
+        m5 = lengths[4],
+        total = lengths.iter().sum::<usize>()
+    );
+    println!("{diagram}");
+}
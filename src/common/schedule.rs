@@ -0,0 +1,113 @@
+// Key schedule of a completed session, run by both sides with the HKDF of the
+// suite hash:
+//
+//   prk := HKDF-Extract(salt = th_5, ikm = k)
+//   secret(label) := HKDF-Expand(prk, enc(label), length)
+//
+// It gives an encryption key and a confirmation key for each direction, a
+// resumption secret and an exporter secret. Applications derive their own keys
+// with `export_keying_material`, as with the TLS exporter (RFC 8446, 7.5):
+//
+//   HKDF-Expand(exporter secret, enc("exporter") || enc(label) || enc(context), length)
+//
+// The optional key confirmation of rounds 7 to 9 sends finished tags
+//
+//   MAC(confirmation key, enc(label) || enc(th))
+//
+// with the server key and label over th_5 and the client ones over th_6.
+
+use zeroize::Zeroizing;
+
+use super::{error::Result, secret::Secret, suite::Suite, wire::put_field};
+
+pub const TRAFFIC_KEY_LENGTH: usize = 32;
+
+pub const CLIENT_KEY_LABEL: &[u8] = b"c2s key";
+pub const SERVER_KEY_LABEL: &[u8] = b"s2c key";
+pub const CLIENT_CONFIRMATION_LABEL: &[u8] = b"c2s confirmation";
+pub const SERVER_CONFIRMATION_LABEL: &[u8] = b"s2c confirmation";
+pub const RESUMPTION_LABEL: &[u8] = b"resumption";
+pub const EXPORTER_LABEL: &[u8] = b"exporter";
+pub const SERVER_FINISHED_LABEL: &[u8] = b"server finished";
+pub const CLIENT_FINISHED_LABEL: &[u8] = b"client finished";
+
+#[derive(Clone, Debug)]
+pub struct KeySchedule {
+    // Client to server
+    client_key: Secret<Vec<u8>>,
+    // Server to client
+    server_key: Secret<Vec<u8>>,
+    client_confirmation_key: Secret<Vec<u8>>,
+    server_confirmation_key: Secret<Vec<u8>>,
+    resumption_secret: Secret<Vec<u8>>,
+    exporter_secret: Secret<Vec<u8>>,
+}
+
+fn expand_label<S: Suite>(config: &S, prk: &[u8], label: &[u8], length: usize) -> Result<Vec<u8>> {
+    let mut info = Vec::new();
+    put_field(&mut info, label);
+
+    config.hkdf_expand(prk, &info, length)
+}
+
+pub fn finished_tag<S: Suite>(config: &S, key: &[u8], label: &[u8], th: &[u8]) -> Vec<u8> {
+    let mut input = Vec::new();
+    put_field(&mut input, label);
+    put_field(&mut input, th);
+
+    config.mac(key, &input)
+}
+
+impl KeySchedule {
+    pub fn derive<S: Suite>(config: &S, k: &[u8], th: &[u8]) -> Result<Self> {
+        let prk = Zeroizing::new(config.hkdf_extract(th, k));
+        let secret_length = prk.len();
+        let secret = |label, length| -> Result<Secret<Vec<u8>>> {
+            Ok(Secret::new(expand_label(config, &prk, label, length)?))
+        };
+
+        Ok(KeySchedule {
+            client_key: secret(CLIENT_KEY_LABEL, TRAFFIC_KEY_LENGTH)?,
+            server_key: secret(SERVER_KEY_LABEL, TRAFFIC_KEY_LENGTH)?,
+            client_confirmation_key: secret(CLIENT_CONFIRMATION_LABEL, secret_length)?,
+            server_confirmation_key: secret(SERVER_CONFIRMATION_LABEL, secret_length)?,
+            resumption_secret: secret(RESUMPTION_LABEL, secret_length)?,
+            exporter_secret: secret(EXPORTER_LABEL, secret_length)?,
+        })
+    }
+
+    pub fn get_client_key(&self) -> &[u8] {
+        self.client_key.expose()
+    }
+
+    pub fn get_server_key(&self) -> &[u8] {
+        self.server_key.expose()
+    }
+
+    pub fn get_client_confirmation_key(&self) -> &[u8] {
+        self.client_confirmation_key.expose()
+    }
+
+    pub fn get_server_confirmation_key(&self) -> &[u8] {
+        self.server_confirmation_key.expose()
+    }
+
+    pub fn get_resumption_secret(&self) -> &[u8] {
+        self.resumption_secret.expose()
+    }
+
+    pub fn export_keying_material<S: Suite>(
+        &self,
+        config: &S,
+        label: &[u8],
+        context: &[u8],
+        length: usize,
+    ) -> Result<Vec<u8>> {
+        let mut info = Vec::new();
+        for field in [EXPORTER_LABEL, label, context] {
+            put_field(&mut info, field);
+        }
+
+        config.hkdf_expand(self.exporter_secret.expose(), &info, length)
+    }
+}
@@ -0,0 +1,461 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    time::{Duration, Instant},
+};
+
+use rand::{CryptoRng, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use super::{
+    client::Client,
+    error::{AkeError, Result},
+    protocol::{
+        M1Message, M2Message, M3Message, M4Message, M5Message, M6Message, M7Message, SessionHandle,
+    },
+    schedule::KeySchedule,
+    secret::Secret,
+    suite::{
+        CcaPkeCiphertext, CcaPkePublicKey, CcaPkeSecretKey, PkeCiphertext, PkePublicKey, Sig,
+        SigPublicKey, SigSecretKey, Suite,
+    },
+};
+
+// Sessions kept at once. When m1 arrives with the table full, expired sessions
+// are dropped first and then the oldest one.
+pub const MAX_OPEN_SESSIONS: usize = 1024;
+// Time after m1 past which a session that was not finished is dropped
+pub const SESSION_LIFETIME: Duration = Duration::from_secs(120);
+
+// State of one handshake, created by m1 and filled in by rounds 2 to 6
+struct Session<S: Suite> {
+    opened: Instant,
+    comm: Vec<u8>,
+    ccapke_keys: Option<(CcaPkePublicKey<S>, Secret<CcaPkeSecretKey<S>>)>,
+    ns: Option<Secret<Vec<u8>>>,
+    cis: Vec<PkeCiphertext<S>>,
+    r: Option<Vec<u8>>,
+    comm_server: Option<Vec<u8>>,
+    open_server: Option<Secret<(Vec<u8>, Vec<u8>)>>,
+    ctxi: Option<CcaPkeCiphertext<S>>,
+    k: Option<Secret<Vec<u8>>>,
+    sid: Option<Secret<Vec<u8>>>,
+    // Running transcript hash th_i, see `common::transcript`
+    transcript: Vec<u8>,
+    // Set once round 6 derives k
+    schedule: Option<KeySchedule>,
+    // Client finished tag of m7, see rounds 7 to 9
+    client_finished: Option<Vec<u8>>,
+    confirmed: bool,
+}
+
+impl<S: Suite> Session<S> {
+    fn new(comm: Vec<u8>) -> Self {
+        Session {
+            opened: Instant::now(),
+            comm,
+            ccapke_keys: None,
+            ns: None,
+            cis: Vec::new(),
+            r: None,
+            comm_server: None,
+            open_server: None,
+            ctxi: None,
+            k: None,
+            sid: None,
+            transcript: Vec::new(),
+            schedule: None,
+            client_finished: None,
+            confirmed: false,
+        }
+    }
+}
+
+pub struct Server<S: Suite> {
+    // Only the public keys vk_j: each client keeps its ek_j to itself
+    clients_keys: Vec<PkePublicKey<S>>,
+    signature_keys: (SigPublicKey<S>, Secret<SigSecretKey<S>>),
+    sessions: HashMap<SessionHandle, Session<S>>,
+    // Source of the session handles, seeded from the rng given to `new`
+    handles: ChaCha20Rng,
+    max_sessions: usize,
+    session_lifetime: Duration,
+}
+
+impl<S: Suite> Server<S> {
+    pub fn new<R: RngCore + CryptoRng>(config: &S, rng: &mut R) -> Result<Self> {
+        let signature_keys = config.sig().sig_gen(rng)?;
+        let handles = ChaCha20Rng::from_rng(rng).map_err(|_| AkeError::Primitive("RNG"))?;
+
+        Ok(Server {
+            handles,
+            ..Server::from_signature_keys(signature_keys)
+        })
+    }
+
+    // Server with an existing signing key pair, e.g. loaded from disk
+    pub fn from_signature_keys(signature_keys: (SigPublicKey<S>, SigSecretKey<S>)) -> Self {
+        Server {
+            clients_keys: Vec::new(),
+            signature_keys: (signature_keys.0, Secret::new(signature_keys.1)),
+            sessions: HashMap::new(),
+            handles: ChaCha20Rng::from_entropy(),
+            max_sessions: MAX_OPEN_SESSIONS,
+            session_lifetime: SESSION_LIFETIME,
+        }
+    }
+
+    // Replaces `MAX_OPEN_SESSIONS` and `SESSION_LIFETIME`
+    pub fn set_session_limits(&mut self, max_sessions: usize, lifetime: Duration) {
+        self.max_sessions = max_sessions.max(1);
+        self.session_lifetime = lifetime;
+    }
+
+    // Opens a session for m1 and returns its fresh random handle. Makes room
+    // first if `max_sessions` are already open, see `MAX_OPEN_SESSIONS`.
+    pub fn receive_m1(&mut self, m1: M1Message) -> SessionHandle {
+        self.expire_sessions();
+        while self.sessions.len() >= self.max_sessions {
+            let Some(oldest) = self
+                .sessions
+                .iter()
+                .min_by_key(|(_, session)| session.opened)
+                .map(|(&handle, _)| handle)
+            else {
+                break;
+            };
+            self.abort_session(oldest);
+        }
+
+        let mut handle: SessionHandle = self.handles.gen();
+        while self.sessions.contains_key(&handle) {
+            handle = self.handles.gen();
+        }
+        self.sessions.insert(handle, Session::new(m1));
+
+        handle
+    }
+
+    pub fn has_session(&self, handle: SessionHandle) -> bool {
+        self.sessions.contains_key(&handle)
+    }
+
+    // Number of sessions currently kept: neither finished, aborted nor expired
+    pub fn sessions_number(&self) -> usize {
+        self.sessions.len()
+    }
+
+    // Drops the sessions opened more than `session_lifetime` ago and returns
+    // how many there were
+    pub fn expire_sessions(&mut self) -> usize {
+        let lifetime = self.session_lifetime;
+        let before = self.sessions.len();
+        self.sessions
+            .retain(|_, session| session.opened.elapsed() < lifetime);
+
+        before - self.sessions.len()
+    }
+
+    // Drops a session whose key was derived, once the application has taken
+    // what it needs from it. Its k, sid and key schedule are wiped.
+    pub fn finish_session(&mut self, handle: SessionHandle) -> Result<()> {
+        if self.session(handle)?.k.is_none() {
+            return Err(AkeError::MissingState("session key"));
+        }
+        self.sessions.remove(&handle);
+
+        Ok(())
+    }
+
+    // Drops a session at any round, e.g. after a failed verification or a lost
+    // connection. Returns false if there was no such session.
+    pub fn abort_session(&mut self, handle: SessionHandle) -> bool {
+        self.sessions.remove(&handle).is_some()
+    }
+
+    // Wipes what only the handshake needs (sk*, n_S, r, openings, ciphertexts)
+    // once round 6 has derived k. Only k, sid, the key schedule and the
+    // transcript are kept for rounds 7 to 9 and the application.
+    pub fn end_handshake(&mut self, handle: SessionHandle) -> Result<()> {
+        let session = self.session_mut(handle)?;
+        session.comm = Vec::new();
+        session.ccapke_keys = None;
+        session.ns = None;
+        session.cis = Vec::new();
+        session.r = None;
+        session.comm_server = None;
+        session.open_server = None;
+        session.ctxi = None;
+
+        Ok(())
+    }
+
+    fn session(&self, handle: SessionHandle) -> Result<&Session<S>> {
+        self.sessions
+            .get(&handle)
+            .ok_or(AkeError::MissingState("session for this handle"))
+    }
+
+    fn session_mut(&mut self, handle: SessionHandle) -> Result<&mut Session<S>> {
+        self.sessions
+            .get_mut(&handle)
+            .ok_or(AkeError::MissingState("session for this handle"))
+    }
+
+    pub fn get_ccapke_keypair(
+        &self,
+        handle: SessionHandle,
+    ) -> Result<(&CcaPkePublicKey<S>, &CcaPkeSecretKey<S>)> {
+        self.session(handle)?
+            .ccapke_keys
+            .as_ref()
+            .map(|(pk, sk)| (pk, sk.expose()))
+            .ok_or(AkeError::MissingState("CCA-PKE key pair (pk*, sk*)"))
+    }
+
+    pub fn set_ccapke_keypair(
+        &mut self,
+        keys: (CcaPkePublicKey<S>, CcaPkeSecretKey<S>),
+        handle: SessionHandle,
+    ) -> Result<()> {
+        let (pk, sk) = keys;
+        self.session_mut(handle)?.ccapke_keys = Some((pk, Secret::new(sk)));
+
+        Ok(())
+    }
+
+    // Adds vk to the registry and returns the index assigned to its owner
+    pub fn add_key(&mut self, vk: PkePublicKey<S>) -> u32 {
+        self.clients_keys.push(vk);
+
+        (self.clients_keys.len() - 1) as u32
+    }
+
+    pub fn get_clients_keys(&self) -> Vec<PkePublicKey<S>> {
+        self.clients_keys.clone()
+    }
+
+    pub fn get_comm(&self, handle: SessionHandle) -> Result<Vec<u8>> {
+        Ok(self.session(handle)?.comm.clone())
+    }
+
+    pub fn get_comm_server(&self, handle: SessionHandle) -> Result<Vec<u8>> {
+        self.session(handle)?
+            .comm_server
+            .clone()
+            .ok_or(AkeError::MissingState("m3 commitment comm_S"))
+    }
+
+    pub fn get_open_server(&self, handle: SessionHandle) -> Result<&(Vec<u8>, Vec<u8>)> {
+        self.session(handle)?
+            .open_server
+            .as_ref()
+            .map(Secret::expose)
+            .ok_or(AkeError::MissingState("m5 opening open_S"))
+    }
+
+    pub fn get_ctxi(&self, handle: SessionHandle) -> Result<CcaPkeCiphertext<S>> {
+        self.session(handle)?
+            .ctxi
+            .clone()
+            .ok_or(AkeError::MissingState("m5 ciphertext ctx_i"))
+    }
+
+    pub fn set_ns(&mut self, handle: SessionHandle, ns: &[u8]) -> Result<()> {
+        self.session_mut(handle)?.ns = Some(Secret::new(ns.to_owned()));
+
+        Ok(())
+    }
+
+    pub fn set_k(&mut self, k: Vec<u8>, handle: SessionHandle) -> Result<()> {
+        self.session_mut(handle)?.k = Some(Secret::new(k));
+
+        Ok(())
+    }
+
+    pub fn set_sid(&mut self, sid: Vec<u8>, handle: SessionHandle) -> Result<()> {
+        self.session_mut(handle)?.sid = Some(Secret::new(sid));
+
+        Ok(())
+    }
+
+    pub fn set_transcript(&mut self, handle: SessionHandle, th: Vec<u8>) -> Result<()> {
+        self.session_mut(handle)?.transcript = th;
+
+        Ok(())
+    }
+
+    pub fn get_transcript(&self, handle: SessionHandle) -> Result<&[u8]> {
+        Ok(&self.session(handle)?.transcript)
+    }
+
+    pub fn set_key_schedule(&mut self, handle: SessionHandle, schedule: KeySchedule) -> Result<()> {
+        self.session_mut(handle)?.schedule = Some(schedule);
+
+        Ok(())
+    }
+
+    pub fn get_key_schedule(&self, handle: SessionHandle) -> Result<&KeySchedule> {
+        self.session(handle)?
+            .schedule
+            .as_ref()
+            .ok_or(AkeError::MissingState("key schedule"))
+    }
+
+    pub fn export_keying_material(
+        &self,
+        config: &S,
+        handle: SessionHandle,
+        label: &[u8],
+        context: &[u8],
+        length: usize,
+    ) -> Result<Vec<u8>> {
+        self.get_key_schedule(handle)?
+            .export_keying_material(config, label, context, length)
+    }
+
+    pub fn get_key(&self, handle: SessionHandle) -> Result<&[u8]> {
+        self.session(handle)?
+            .k
+            .as_ref()
+            .map(|k| k.expose().as_slice())
+            .ok_or(AkeError::MissingState("session key"))
+    }
+
+    pub fn get_sid(&self, handle: SessionHandle) -> Result<&[u8]> {
+        self.session(handle)?
+            .sid
+            .as_ref()
+            .map(|sid| sid.expose().as_slice())
+            .ok_or(AkeError::MissingState("session identifier"))
+    }
+
+    pub fn get_ns(&self, handle: SessionHandle) -> Result<&[u8]> {
+        self.session(handle)?
+            .ns
+            .as_ref()
+            .map(|ns| ns.expose().as_slice())
+            .ok_or(AkeError::MissingState("server nonce n_S"))
+    }
+
+    pub fn get_sig_keypair(&self) -> (&SigPublicKey<S>, &SigSecretKey<S>) {
+        (&self.signature_keys.0, self.signature_keys.1.expose())
+    }
+
+    pub fn get_sig_pk(&self) -> SigPublicKey<S> {
+        self.signature_keys.0.clone()
+    }
+
+    pub fn add_ciphertexts(
+        &mut self,
+        handle: SessionHandle,
+        cis: &[PkeCiphertext<S>],
+        r: &[u8],
+    ) -> Result<()> {
+        let session = self.session_mut(handle)?;
+        session.cis = cis.to_owned();
+        session.r = Some(r.to_owned());
+
+        Ok(())
+    }
+
+    pub fn get_r(&self, handle: SessionHandle) -> Result<Vec<u8>> {
+        self.session(handle)?
+            .r
+            .clone()
+            .ok_or(AkeError::MissingState("m2 randomness r"))
+    }
+
+    pub fn get_cis(&self, handle: SessionHandle) -> Result<Vec<PkeCiphertext<S>>> {
+        Ok(self.session(handle)?.cis.clone())
+    }
+
+    pub fn send_m2(&self, m2: M2Message<S>, client: &mut Client<S>) {
+        client.receive_m2(m2);
+    }
+
+    // Messages for an unknown handle are dropped, the round that needs them
+    // then reports the missing state
+    pub fn receive_m3(&mut self, m3: M3Message) {
+        let (comm_s, handle) = m3;
+        if let Ok(session) = self.session_mut(handle) {
+            session.comm_server = Some(comm_s);
+        }
+    }
+
+    pub fn send_m4(&self, m4: M4Message<S>, client: &mut Client<S>) {
+        client.receive_m4(m4);
+    }
+
+    pub fn receive_m5(&mut self, m5: M5Message<S>) {
+        let (ctxi, open_s, handle) = m5;
+        if let Ok(session) = self.session_mut(handle) {
+            session.open_server = Some(Secret::new(open_s));
+            session.ctxi = Some(ctxi);
+        }
+    }
+
+    pub fn send_m6(&self, m6: M6Message, client: &mut Client<S>) {
+        client.receive_m6(m6);
+    }
+
+    pub fn receive_m7(&mut self, m7: M7Message) {
+        let (tag, handle) = m7;
+        if let Ok(session) = self.session_mut(handle) {
+            session.client_finished = Some(tag);
+        }
+    }
+
+    pub fn get_client_finished(&self, handle: SessionHandle) -> Result<Vec<u8>> {
+        self.session(handle)?
+            .client_finished
+            .clone()
+            .ok_or(AkeError::MissingState("client finished tag"))
+    }
+
+    pub fn set_confirmed(&mut self, handle: SessionHandle) -> Result<()> {
+        self.session_mut(handle)?.confirmed = true;
+
+        Ok(())
+    }
+
+    // True once round 9 has verified the client finished tag
+    pub fn is_confirmed(&self, handle: SessionHandle) -> Result<bool> {
+        Ok(self.session(handle)?.confirmed)
+    }
+}
+
+// Written by hand so that `S` needs no `Debug`. Secrets print as `<redacted>`.
+impl<S: Suite> fmt::Debug for Session<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("opened", &self.opened)
+            .field("comm", &self.comm)
+            .field("ccapke_keys", &self.ccapke_keys)
+            .field("ns", &self.ns)
+            .field("cis", &self.cis)
+            .field("r", &self.r)
+            .field("comm_server", &self.comm_server)
+            .field("open_server", &self.open_server)
+            .field("ctxi", &self.ctxi)
+            .field("k", &self.k)
+            .field("sid", &self.sid)
+            .field("transcript", &self.transcript)
+            .field("schedule", &self.schedule)
+            .field("client_finished", &self.client_finished)
+            .field("confirmed", &self.confirmed)
+            .finish()
+    }
+}
+
+impl<S: Suite> fmt::Debug for Server<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Server")
+            .field("clients_keys", &self.clients_keys)
+            .field("signature_keys", &self.signature_keys)
+            .field("sessions", &self.sessions)
+            .field("max_sessions", &self.max_sessions)
+            .field("session_lifetime", &self.session_lifetime)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,328 @@
+// Server and client state kept on disk between runs of `serve` and `connect`
+//
+//   <dir>/server.key   := header || pk_S || sk_S
+//   <dir>/server.pub   := header || pk_S
+//   <dir>/registry     := header || l (u32) || vk_1 || ... || vk_l
+//   <client>.key       := header || vk_i || ek_i
+//   <client>.pub       := header || vk_i
+//
+// Every file starts with
+//
+//   version (u8) || suite identifier (u8) || file type (u8) || algorithm
+//
+// where algorithm is the name of the signature scheme for the server files and
+// of the PKE for the others. Loading a file written for another version, suite,
+// file type or algorithm fails.
+//
+// `server.pub` and `registry` are public and are what clients need to run the
+// handshake. `server.key` and `<client>.key` are created readable by their
+// owner only (0600 on Unix) and are never overwritten. The server never sees
+// ek_i: clients generate their key pair locally and submit only
+// `<client>.pub`.
+//
+// Fields use the length-prefixed encoding of `common::wire`.
+
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use rand::rngs::OsRng;
+
+use super::{
+    client::Client,
+    error::{AkeError, Result},
+    protocol::client_keygen,
+    server::Server,
+    suite::{Pke, PkePublicKey, PkeSecretKey, Sig, SigPublicKey, Suite},
+    wire::{put_field, Reader},
+};
+
+pub const KEY_FILE_VERSION: u8 = 1;
+
+const SERVER_KEY_FILE: &str = "server.key";
+const SERVER_PUBLIC_KEY_FILE: &str = "server.pub";
+const REGISTRY_FILE: &str = "registry";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum KeyFileType {
+    ServerKey = 1,
+    ServerPublicKey = 2,
+    Registry = 3,
+    ClientKey = 4,
+    ClientPublicKey = 5,
+}
+
+fn key_file_header<S: Suite>(config: &S, file_type: KeyFileType, algorithm: &str) -> Vec<u8> {
+    let mut out = vec![KEY_FILE_VERSION, config.suite_id(), file_type as u8];
+    put_field(&mut out, algorithm.as_bytes());
+
+    out
+}
+
+fn read_key_file<'a, S: Suite>(
+    config: &S,
+    bytes: &'a [u8],
+    file_type: KeyFileType,
+    algorithm: &str,
+) -> Result<Reader<'a>> {
+    let mut reader = Reader::from_bytes(bytes);
+    let header = reader.take(3)?;
+    if header[0] != KEY_FILE_VERSION {
+        return Err(AkeError::Decoding("key file: version"));
+    }
+    if header[1] != config.suite_id() {
+        return Err(AkeError::Decoding("key file: suite identifier"));
+    }
+    if header[2] != file_type as u8 {
+        return Err(AkeError::Decoding("key file: file type"));
+    }
+    if reader.field()? != algorithm.as_bytes() {
+        return Err(AkeError::Decoding("key file: algorithm"));
+    }
+
+    Ok(reader)
+}
+
+fn pke_header<S: Suite>(config: &S, file_type: KeyFileType) -> Vec<u8> {
+    key_file_header(config, file_type, &config.pke().algorithm_name())
+}
+
+fn sig_header<S: Suite>(config: &S, file_type: KeyFileType) -> Vec<u8> {
+    key_file_header(config, file_type, &config.sig().algorithm_name())
+}
+
+fn read_pke_file<'a, S: Suite>(
+    config: &S,
+    bytes: &'a [u8],
+    file_type: KeyFileType,
+) -> Result<Reader<'a>> {
+    read_key_file(config, bytes, file_type, &config.pke().algorithm_name())
+}
+
+fn read_sig_file<'a, S: Suite>(
+    config: &S,
+    bytes: &'a [u8],
+    file_type: KeyFileType,
+) -> Result<Reader<'a>> {
+    read_key_file(config, bytes, file_type, &config.sig().algorithm_name())
+}
+
+// Creates a secret key file, failing if it exists
+fn write_secret_file(path: &Path, bytes: &[u8]) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    options.open(path)?.write_all(bytes)?;
+
+    Ok(())
+}
+
+// `<client>.pub` next to `<client>.key`
+pub fn public_key_path(key_path: &Path) -> PathBuf {
+    key_path.with_extension("pub")
+}
+
+pub fn save_client_keys<S: Suite>(
+    config: &S,
+    path: &Path,
+    keys: &(PkePublicKey<S>, PkeSecretKey<S>),
+) -> Result<()> {
+    let (vk, ek) = keys;
+    let vk_bytes = config.pke().public_key_to_bytes(vk);
+
+    let mut out = pke_header(config, KeyFileType::ClientKey);
+    put_field(&mut out, &vk_bytes);
+    put_field(&mut out, &config.pke().secret_key_to_bytes(ek));
+    write_secret_file(path, &out)?;
+
+    let mut out = pke_header(config, KeyFileType::ClientPublicKey);
+    put_field(&mut out, &vk_bytes);
+    fs::write(public_key_path(path), out)?;
+
+    Ok(())
+}
+
+pub fn load_client_keys<S: Suite>(
+    config: &S,
+    path: &Path,
+) -> Result<(PkePublicKey<S>, PkeSecretKey<S>)> {
+    let bytes = fs::read(path)?;
+    let mut reader = read_pke_file(config, &bytes, KeyFileType::ClientKey)?;
+    let vk = config.pke().public_key_from_bytes(reader.field()?)?;
+    let ek = config.pke().secret_key_from_bytes(reader.field()?)?;
+    reader.finish()?;
+
+    Ok((vk, ek))
+}
+
+pub fn load_client_public_key<S: Suite>(config: &S, path: &Path) -> Result<PkePublicKey<S>> {
+    let bytes = fs::read(path)?;
+    let mut reader = read_pke_file(config, &bytes, KeyFileType::ClientPublicKey)?;
+    let vk = config.pke().public_key_from_bytes(reader.field()?)?;
+    reader.finish()?;
+
+    Ok(vk)
+}
+
+// Client side of registration: generates (vk, ek) and writes `path` and its
+// `.pub` companion. An existing key is never overwritten.
+pub fn client_keygen_to_file<S: Suite>(config: &S, path: &Path) -> Result<PkePublicKey<S>> {
+    let keys = client_keygen(config, &mut OsRng)?;
+    save_client_keys(config, path, &keys)?;
+
+    Ok(keys.0)
+}
+
+fn save_server_keys<S: Suite>(config: &S, dir: &Path, server: &Server<S>) -> Result<()> {
+    fs::create_dir_all(dir)?;
+
+    let (pk_s, sk_s) = server.get_sig_keypair();
+    let pk_bytes = config.sig().public_key_to_bytes(pk_s);
+
+    let mut out = sig_header(config, KeyFileType::ServerKey);
+    put_field(&mut out, &pk_bytes);
+    put_field(&mut out, &config.sig().secret_key_to_bytes(sk_s));
+    write_secret_file(&dir.join(SERVER_KEY_FILE), &out)?;
+
+    let mut out = sig_header(config, KeyFileType::ServerPublicKey);
+    put_field(&mut out, &pk_bytes);
+    fs::write(dir.join(SERVER_PUBLIC_KEY_FILE), out)?;
+
+    Ok(())
+}
+
+fn save_registry<S: Suite>(config: &S, dir: &Path, vks: &[PkePublicKey<S>]) -> Result<()> {
+    let mut out = pke_header(config, KeyFileType::Registry);
+    out.extend_from_slice(&(vks.len() as u32).to_be_bytes());
+    for vk in vks.iter() {
+        put_field(&mut out, &config.pke().public_key_to_bytes(vk));
+    }
+    fs::write(dir.join(REGISTRY_FILE), out)?;
+
+    Ok(())
+}
+
+pub fn load_registry<S: Suite>(config: &S, dir: &Path) -> Result<Vec<PkePublicKey<S>>> {
+    let path = dir.join(REGISTRY_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let bytes = fs::read(path)?;
+    let mut reader = read_pke_file(config, &bytes, KeyFileType::Registry)?;
+    let l = reader.u32()?;
+    let mut vks = Vec::new();
+    for _ in 0..l {
+        vks.push(config.pke().public_key_from_bytes(reader.field()?)?);
+    }
+    reader.finish()?;
+
+    Ok(vks)
+}
+
+pub fn load_server_public_key<S: Suite>(config: &S, dir: &Path) -> Result<SigPublicKey<S>> {
+    let bytes = fs::read(dir.join(SERVER_PUBLIC_KEY_FILE))?;
+    let mut reader = read_sig_file(config, &bytes, KeyFileType::ServerPublicKey)?;
+    let pk_s = config.sig().public_key_from_bytes(reader.field()?)?;
+    reader.finish()?;
+
+    Ok(pk_s)
+}
+
+// Generates the server signing key pair and writes `server.key` and
+// `server.pub`. An existing key is never overwritten.
+pub fn server_keygen<S: Suite>(config: &S, dir: &Path) -> Result<Server<S>> {
+    if dir.join(SERVER_KEY_FILE).exists() {
+        return Err(AkeError::Io(io::ErrorKind::AlreadyExists));
+    }
+    let server = Server::new(config, &mut OsRng)?;
+    save_server_keys(config, dir, &server)?;
+
+    Ok(server)
+}
+
+// Server with the signing key pair of `server.key` and no registered client
+pub fn load_server_keys<S: Suite>(config: &S, dir: &Path) -> Result<Server<S>> {
+    let bytes = fs::read(dir.join(SERVER_KEY_FILE))?;
+    let mut reader = read_sig_file(config, &bytes, KeyFileType::ServerKey)?;
+    let pk_s = config.sig().public_key_from_bytes(reader.field()?)?;
+    let sk_s = config.sig().secret_key_from_bytes(reader.field()?)?;
+    reader.finish()?;
+
+    Ok(Server::from_signature_keys((pk_s, sk_s)))
+}
+
+// Loads the server signing key, creating it on first use, and the registry
+fn load_or_create_server<S: Suite>(config: &S, dir: &Path) -> Result<Server<S>> {
+    let mut server = if dir.join(SERVER_KEY_FILE).exists() {
+        load_server_keys(config, dir)?
+    } else {
+        server_keygen(config, dir)?
+    };
+
+    for vk in load_registry(config, dir)? {
+        server.add_key(vk);
+    }
+
+    Ok(server)
+}
+
+// Server side of registration: appends vk to the registry and returns the
+// index of its owner. Registering the same vk twice returns the same index.
+pub fn register_client<S: Suite>(config: &S, dir: &Path, vk: &PkePublicKey<S>) -> Result<u32> {
+    let server = load_or_create_server(config, dir)?;
+    let mut vks = server.get_clients_keys();
+    let vk_bytes = config.pke().public_key_to_bytes(vk);
+
+    if let Some(id) = vks
+        .iter()
+        .position(|x| config.pke().public_key_to_bytes(x) == vk_bytes)
+    {
+        return Ok(id as u32);
+    }
+
+    vks.push(vk.to_owned());
+    save_registry(config, dir, &vks)?;
+
+    Ok((vks.len() - 1) as u32)
+}
+
+// Server ready to run handshakes: `config.get_users_number()` clients must be
+// registered
+pub fn load_server<S: Suite>(config: &S, dir: &Path) -> Result<Server<S>> {
+    let server = load_or_create_server(config, dir)?;
+    if server.get_clients_keys().len() != config.get_users_number() as usize {
+        return Err(AkeError::Decoding("registry: number of clients"));
+    }
+
+    Ok(server)
+}
+
+// Client ready to run a handshake: its key pair from `key_path` plus the
+// public registry and server key from `dir`. The client index is the position
+// of its vk in the registry.
+pub fn load_client<S: Suite>(config: &S, key_path: &Path, dir: &Path) -> Result<Client<S>> {
+    let (vk, ek) = load_client_keys(config, key_path)?;
+    let vks = load_registry(config, dir)?;
+    if vks.len() != config.get_users_number() as usize {
+        return Err(AkeError::Decoding("registry: number of clients"));
+    }
+    let vk_bytes = config.pke().public_key_to_bytes(&vk);
+    let id = vks
+        .iter()
+        .position(|x| config.pke().public_key_to_bytes(x) == vk_bytes)
+        .ok_or(AkeError::MissingState("registration of this client"))?;
+
+    let mut client = Client::new(id as u32);
+    client.set_ek(ek);
+    client.set_pks(load_server_public_key(config, dir)?);
+    client.set_vks(vks);
+
+    Ok(client)
+}
@@ -0,0 +1,162 @@
+use std::fmt::Debug;
+
+use hkdf::SimpleHkdf;
+use hmac::{
+    digest::{core_api::BlockSizeUser, Digest},
+    Mac, SimpleHmac,
+};
+use rand::{CryptoRng, RngCore};
+
+use super::{
+    error::{AkeError, Result},
+    secret::Wipe,
+};
+
+// Building blocks of the protocol. Each suite (pq, classic) provides one
+// implementation of every primitive and ties them together through `Suite`.
+// Randomised operations draw from the `rng` they are given, so a seeded
+// generator reproduces their output.
+
+pub trait Pke {
+    type PublicKey: Clone + Debug;
+    type SecretKey: Clone + Debug + Wipe;
+    type Ciphertext: Clone + Debug;
+
+    // Recorded in key files so that keys are never loaded into another scheme
+    fn algorithm_name(&self) -> String;
+    fn pke_gen<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(Self::PublicKey, Self::SecretKey)>;
+    fn pke_enc(&self, pk: &Self::PublicKey, m: &[u8], r: &[u8]) -> Result<Self::Ciphertext>;
+    fn pke_dec(&self, sk: &Self::SecretKey, ct: &Self::Ciphertext) -> Result<Vec<u8>>;
+    fn check_ciphertext(&self, c1: &Self::Ciphertext, c2: &Self::Ciphertext) -> bool;
+    // Randomness r sent in m2, from which the coins of every c_j are derived
+    fn pke_randomness<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Result<Vec<u8>>;
+    fn public_key_to_bytes(&self, pk: &Self::PublicKey) -> Vec<u8>;
+    fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<Self::PublicKey>;
+    fn secret_key_to_bytes(&self, sk: &Self::SecretKey) -> Vec<u8>;
+    fn secret_key_from_bytes(&self, bytes: &[u8]) -> Result<Self::SecretKey>;
+    fn ciphertext_to_bytes(&self, ct: &Self::Ciphertext) -> Vec<u8>;
+    fn ciphertext_from_bytes(&self, bytes: &[u8]) -> Result<Self::Ciphertext>;
+}
+
+pub trait CcaPke {
+    type PublicKey: Clone + Debug;
+    type SecretKey: Clone + Debug + Wipe;
+    type Ciphertext: Clone + Debug;
+
+    fn algorithm_name(&self) -> String;
+    fn ccapke_gen<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(Self::PublicKey, Self::SecretKey)>;
+    fn ccapke_enc<R: RngCore + CryptoRng>(
+        &self,
+        pk: &Self::PublicKey,
+        m: &[u8],
+        rng: &mut R,
+    ) -> Result<Self::Ciphertext>;
+    fn ccapke_dec(&self, sk: &Self::SecretKey, ct: &Self::Ciphertext) -> Result<Vec<u8>>;
+    fn public_key_to_bytes(&self, pk: &Self::PublicKey) -> Vec<u8>;
+    fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<Self::PublicKey>;
+    fn ciphertext_to_bytes(&self, ct: &Self::Ciphertext) -> Vec<u8>;
+    fn ciphertext_from_bytes(&self, bytes: &[u8]) -> Result<Self::Ciphertext>;
+}
+
+pub trait Sig {
+    type PublicKey: Clone + Debug;
+    type SecretKey: Clone + Debug + Wipe;
+    type Signature: Clone + Debug;
+
+    fn algorithm_name(&self) -> String;
+    fn sig_gen<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(Self::PublicKey, Self::SecretKey)>;
+    fn sig_sign(&self, sk: &Self::SecretKey, m: &[u8]) -> Result<Self::Signature>;
+    fn sig_vry(&self, pk: &Self::PublicKey, m: &[u8], signature: &Self::Signature) -> bool;
+    fn public_key_to_bytes(&self, pk: &Self::PublicKey) -> Vec<u8>;
+    fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<Self::PublicKey>;
+    fn secret_key_to_bytes(&self, sk: &Self::SecretKey) -> Vec<u8>;
+    fn secret_key_from_bytes(&self, bytes: &[u8]) -> Result<Self::SecretKey>;
+    fn signature_to_bytes(&self, signature: &Self::Signature) -> Vec<u8>;
+    fn signature_from_bytes(&self, bytes: &[u8]) -> Result<Self::Signature>;
+}
+
+pub trait Comm {
+    // Output: commitment and open := (x, r)
+    fn comm<R: RngCore + CryptoRng>(&self, x: &[u8], rng: &mut R) -> (Vec<u8>, (Vec<u8>, Vec<u8>));
+    fn comm_vfy(&self, comm: &[u8], open: &(Vec<u8>, Vec<u8>)) -> bool;
+}
+
+pub trait Prf {
+    fn algorithm_name(&self) -> String;
+    // `length` bytes of PRF_key(label, index)
+    fn prf(&self, key: &[u8], label: &[u8], index: u64, length: usize) -> Result<Vec<u8>>;
+}
+
+pub trait Suite {
+    type Pke: Pke;
+    type CcaPke: CcaPke;
+    type Sig: Sig;
+    type Comm: Comm;
+    type Prf: Prf;
+    // Suite hash, which also instantiates HKDF and HMAC
+    type Hash: Digest + BlockSizeUser + Clone;
+
+    fn pke(&self) -> &Self::Pke;
+    fn ccapke(&self) -> &Self::CcaPke;
+    fn sig(&self) -> &Self::Sig;
+    fn comm(&self) -> &Self::Comm;
+    fn prf(&self) -> &Self::Prf;
+    // Suite identifier carried in the header of every wire message
+    fn suite_id(&self) -> u8;
+    fn get_users_number(&self) -> u32;
+
+    fn hash(&self, m: &[u8]) -> Vec<u8> {
+        Self::Hash::digest(m).to_vec()
+    }
+
+    // HKDF (RFC 5869) instantiated with the suite hash
+    fn hkdf_extract(&self, salt: &[u8], ikm: &[u8]) -> Vec<u8> {
+        let (prk, _) = SimpleHkdf::<Self::Hash>::extract(Some(salt), ikm);
+        prk.to_vec()
+    }
+
+    fn hkdf_expand(&self, prk: &[u8], info: &[u8], length: usize) -> Result<Vec<u8>> {
+        let hkdf =
+            SimpleHkdf::<Self::Hash>::from_prk(prk).map_err(|_| AkeError::Primitive("HKDF"))?;
+        let mut okm = vec![0u8; length];
+        hkdf.expand(info, &mut okm)
+            .map_err(|_| AkeError::Primitive("HKDF"))?;
+
+        Ok(okm)
+    }
+
+    // HMAC with the suite hash, used for key confirmation
+    fn mac(&self, key: &[u8], m: &[u8]) -> Vec<u8> {
+        // HMAC takes keys of any length
+        let mut mac = <SimpleHmac<Self::Hash> as Mac>::new_from_slice(key).expect("HMAC key");
+        mac.update(m);
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    // Coins used to encrypt n_S for recipient j: r_j <- PRF(r, j), as long as r
+    fn pke_coins(&self, r: &[u8], j: u32) -> Result<Vec<u8>> {
+        self.prf().prf(r, PKE_COINS_LABEL, j as u64, r.len())
+    }
+}
+
+// PRF label of the coins r_j
+pub const PKE_COINS_LABEL: &[u8] = b"pke coins";
+
+pub type PkePublicKey<S> = <<S as Suite>::Pke as Pke>::PublicKey;
+pub type PkeSecretKey<S> = <<S as Suite>::Pke as Pke>::SecretKey;
+pub type PkeCiphertext<S> = <<S as Suite>::Pke as Pke>::Ciphertext;
+pub type CcaPkePublicKey<S> = <<S as Suite>::CcaPke as CcaPke>::PublicKey;
+pub type CcaPkeSecretKey<S> = <<S as Suite>::CcaPke as CcaPke>::SecretKey;
+pub type CcaPkeCiphertext<S> = <<S as Suite>::CcaPke as CcaPke>::Ciphertext;
+pub type SigPublicKey<S> = <<S as Suite>::Sig as Sig>::PublicKey;
+pub type SigSecretKey<S> = <<S as Suite>::Sig as Sig>::SecretKey;
+pub type Signature<S> = <<S as Suite>::Sig as Sig>::Signature;
@@ -0,0 +1,53 @@
+// Running hash of a handshake, kept by both sides with the suite hash H:
+//
+//   th_0 := H(enc("transcript") || suite identifier || l (u32) || enc(pk_S))
+//   th_i := H(enc(th_{i-1}) || enc(m_i))        i = 1, ..., 5
+//
+// where m_i is the wire encoding of the message and enc(x) := len(x) (u32) || x.
+// The session key and sid are derived from n_S || n_i || th_5 under distinct
+// labels, so they depend on every message, on pk_S and on the suite.
+
+use zeroize::Zeroizing;
+
+use super::{
+    suite::{Sig, SigPublicKey, Suite},
+    wire::put_field,
+};
+
+pub const TRANSCRIPT_LABEL: &[u8] = b"transcript";
+pub const SESSION_KEY_LABEL: &[u8] = b"session key";
+pub const SID_LABEL: &[u8] = b"sid";
+
+pub fn transcript_start<S: Suite>(config: &S, pk_s: &SigPublicKey<S>) -> Vec<u8> {
+    let mut input = Vec::new();
+    put_field(&mut input, TRANSCRIPT_LABEL);
+    input.push(config.suite_id());
+    input.extend_from_slice(&config.get_users_number().to_be_bytes());
+    put_field(&mut input, &config.sig().public_key_to_bytes(pk_s));
+
+    config.hash(&input)
+}
+
+pub fn transcript_update<S: Suite>(config: &S, th: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut input = Vec::new();
+    put_field(&mut input, th);
+    put_field(&mut input, message);
+
+    config.hash(&input)
+}
+
+// H(enc(label) || enc(n_S) || enc(n_i) || enc(th))
+pub fn derive_session_secret<S: Suite>(
+    config: &S,
+    label: &[u8],
+    ns: &[u8],
+    ni: &[u8],
+    th: &[u8],
+) -> Vec<u8> {
+    let mut input = Zeroizing::new(Vec::new());
+    for field in [label, ns, ni, th] {
+        put_field(&mut input, field);
+    }
+
+    config.hash(&input)
+}
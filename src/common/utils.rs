@@ -1,6 +1,11 @@
+use std::process;
+
 use hex;
 use rand::thread_rng;
-use rand::Rng;
+use rand::{CryptoRng, Rng, RngCore};
+use subtle::ConstantTimeEq;
+
+use super::error::AkeError;
 
 pub fn get_random_key32() -> Vec<u8> {
     let mut x = vec![0; 32];
@@ -10,6 +15,12 @@ pub fn get_random_key32() -> Vec<u8> {
     x
 }
 
+pub fn get_random_bytes<R: RngCore + CryptoRng>(rng: &mut R, n: usize) -> Vec<u8> {
+    let mut x = vec![0; n];
+    rng.fill_bytes(&mut x);
+    x
+}
+
 pub fn get_nonce() -> Vec<u8> {
     let mut x = vec![0; 12];
     thread_rng()
@@ -18,15 +29,39 @@ pub fn get_nonce() -> Vec<u8> {
     x
 }
 
-pub fn to_hex(arr: &Vec<u8>) -> String {
+pub fn to_hex(arr: &[u8]) -> String {
     hex::encode(arr)
 }
 
-pub fn print_hex(arr: &Vec<u8>, name: &str) {
+pub fn print_hex(arr: &[u8], name: &str) {
     println!("{:} 0x{:}", name, to_hex(arr));
 }
 
+// Equality of commitments and ciphertexts. Inputs of different lengths never
+// match, and equal lengths are compared in constant time.
+pub fn ct_eq(x: &[u8], y: &[u8]) -> bool {
+    x.len() == y.len() && bool::from(x.ct_eq(y))
+}
+
 pub fn xor(x: &[u8], y: &[u8]) -> Vec<u8> {
     let z: Vec<u8> = x.iter().zip(y).map(|(a, b)| a ^ b).collect();
     z
 }
+
+// Value of a command-line option that only some modes can do without
+pub fn required<T>(value: Option<T>, option: &str) -> T {
+    value.unwrap_or_else(|| {
+        println!("[!] {option} is required in this mode!");
+        process::exit(1);
+    })
+}
+
+pub fn exit_on_error<T>(result: Result<T, AkeError>, step: &str) -> T {
+    match result {
+        Ok(value) => value,
+        Err(e) => {
+            println!("[!] {step} failed: {e}");
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,184 @@
+// Known-answer vectors of whole handshakes. A vector records the inputs (seed,
+// suite, PRF, number of clients and index of the client) and the outputs: the
+// wire encoding of m1, ..., m5 and the session key and sid, all in hex. The
+// handshake is run with a ChaCha20 generator seeded with `seed`, so the same
+// inputs always give the same outputs.
+//
+// liboqs draws its randomness from the same generator (see `pq::seeded`), so
+// PQ and hybrid handshakes are pinned too, for a given liboqs version.
+
+use std::{fs, path::Path};
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    error::{AkeError, Result},
+    prf::PrfAlgorithm,
+    protocol::{registration, round_1, round_2, round_3, round_4, round_5, round_6},
+    suite::{CcaPke, Pke, Prf, Sig, Suite},
+    wire::{encode_m1, encode_m2, encode_m3, encode_m4, encode_m5},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestVector {
+    pub seed: u64,
+    pub suite: String,
+    pub prf: String,
+    pub users: u32,
+    pub client: u32,
+    pub m1: String,
+    pub m2: String,
+    pub m3: String,
+    pub m4: String,
+    pub m5: String,
+    pub k: String,
+    pub sid: String,
+}
+
+// PKE and signature scheme, e.g. "Kyber768+Dilithium3", with the CCA-PKE
+// after the PKE when it differs, e.g. "ClassicMcEliece348864f/Kyber512+Dilithium2"
+pub fn suite_name<S: Suite>(config: &S) -> String {
+    let pke = config.pke().algorithm_name();
+    let ccapke = config.ccapke().algorithm_name();
+    let sig = config.sig().algorithm_name();
+
+    if pke == ccapke {
+        format!("{pke}+{sig}")
+    } else {
+        format!("{pke}/{ccapke}+{sig}")
+    }
+}
+
+// Number of clients and PRF a vector was generated with
+pub fn vector_parameters(vector: &TestVector) -> Result<(u32, PrfAlgorithm)> {
+    if vector.users == 0 {
+        return Err(AkeError::Decoding("test vector: number of clients"));
+    }
+    let prf = PrfAlgorithm::from_name(&vector.prf).ok_or(AkeError::Decoding("test vector: PRF"))?;
+
+    Ok((vector.users, prf))
+}
+
+// Runs registration and one handshake of client `client` from `seed`
+pub fn generate_vector<S: Suite>(config: &S, seed: u64, client: u32) -> Result<TestVector> {
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    let (mut server, mut clients) = registration(config, &mut rng)?;
+    if client as usize >= clients.len() {
+        return Err(AkeError::MissingState("client with this index"));
+    }
+    let mut c = clients.swap_remove(client as usize);
+
+    let m1 = round_1(&mut c, config, &mut rng)?;
+    let e1 = encode_m1(config, &m1);
+    let handle = c.send_m1(m1, &mut server);
+
+    let m2 = round_2(&mut server, config, handle, &mut rng)?;
+    let e2 = encode_m2(config, &m2);
+    server.send_m2(m2, &mut c);
+
+    let m3 = round_3(&mut c, config, &mut rng, false)?;
+    let e3 = encode_m3(config, &m3);
+    c.send_m3(m3, &mut server);
+
+    let m4 = round_4(&mut server, config, handle)?;
+    let e4 = encode_m4(config, &m4);
+    server.send_m4(m4, &mut c);
+
+    let m5 = round_5(&mut c, config, &mut rng, false)?;
+    let e5 = encode_m5(config, &m5);
+    c.send_m5(m5, &mut server);
+
+    round_6(&mut server, config, handle, false)?;
+
+    Ok(TestVector {
+        seed,
+        suite: suite_name(config),
+        prf: config.prf().algorithm_name(),
+        users: config.get_users_number(),
+        client,
+        m1: hex::encode(e1),
+        m2: hex::encode(e2),
+        m3: hex::encode(e3),
+        m4: hex::encode(e4),
+        m5: hex::encode(e5),
+        k: hex::encode(server.get_key(handle)?),
+        sid: hex::encode(server.get_sid(handle)?),
+    })
+}
+
+// Regenerates `vector` with `config` and reports the first output that differs
+pub fn check_vector<S: Suite>(config: &S, vector: &TestVector) -> Result<()> {
+    if vector.suite != suite_name(config) {
+        return Err(AkeError::Decoding("test vector: suite"));
+    }
+    if vector.prf != config.prf().algorithm_name() {
+        return Err(AkeError::Decoding("test vector: PRF"));
+    }
+    if vector.users != config.get_users_number() {
+        return Err(AkeError::Decoding("test vector: number of clients"));
+    }
+
+    let got = generate_vector(config, vector.seed, vector.client)?;
+    let fields = [
+        (&got.m1, &vector.m1, "test vector m1"),
+        (&got.m2, &vector.m2, "test vector m2"),
+        (&got.m3, &vector.m3, "test vector m3"),
+        (&got.m4, &vector.m4, "test vector m4"),
+        (&got.m5, &vector.m5, "test vector m5"),
+        (&got.k, &vector.k, "test vector key"),
+        (&got.sid, &vector.sid, "test vector sid"),
+    ];
+    for (got, expected, what) in fields {
+        if got != expected {
+            return Err(AkeError::Verification(what));
+        }
+    }
+
+    Ok(())
+}
+
+// Vectors for seeds 0, ..., number - 1, the client being the seed modulo the
+// number of clients
+pub fn generate_vectors<S: Suite>(config: &S, number: u64) -> Result<Vec<TestVector>> {
+    let users = u64::from(config.get_users_number());
+
+    (0..number)
+        .map(|seed| generate_vector(config, seed, (seed % users) as u32))
+        .collect()
+}
+
+// Checks every vector with `check` and prints one line per seed. False if any
+// fails.
+pub fn check_vectors(vectors: &[TestVector], check: impl Fn(&TestVector) -> Result<()>) -> bool {
+    let mut ok = true;
+    for vector in vectors.iter() {
+        match check(vector) {
+            Ok(()) => println!("[!] Seed {} -> OK", vector.seed),
+            Err(e) => {
+                println!("[!] Seed {} -> KO: {e}", vector.seed);
+                ok = false;
+            }
+        }
+    }
+
+    ok
+}
+
+pub fn read_vectors(path: &Path) -> Result<Vec<TestVector>> {
+    let bytes = fs::read(path)?;
+
+    serde_json::from_slice(&bytes).map_err(|_| AkeError::Decoding("test vector file"))
+}
+
+pub fn write_vectors(path: &Path, vectors: &[TestVector]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let json =
+        serde_json::to_string_pretty(vectors).map_err(|_| AkeError::Primitive("JSON encoding"))?;
+    fs::write(path, json + "\n")?;
+
+    Ok(())
+}
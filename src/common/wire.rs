@@ -0,0 +1,281 @@
+// Wire format of m1, ..., m7
+//
+// Every message starts with a 3-byte header
+//
+//   version (u8) || suite identifier (u8) || message type (u8)
+//
+// followed by its fields in order. Variable-length fields are prefixed with
+// their length as a big-endian u32 and session handles are big-endian u64:
+//
+//   m1 := comm_i
+//   m2 := handle || l (u32) || c_1 || ... || c_l || r || pk* || σ_2
+//   m3 := handle || comm_S
+//   m4 := r || σ_4
+//   m5 := handle || ctx_i || x_S || r_S     where open_S := (x_S, r_S)
+//   m6 := server finished tag               (optional key confirmation)
+//   m7 := handle || client finished tag
+//
+// No message carries the client index i.
+//
+// Keys, ciphertexts and signatures are encoded with the `*_to_bytes` methods
+// of the suite primitives. Decoding rejects a version, suite or message type
+// other than the expected one and any trailing bytes.
+
+use super::{
+    error::{AkeError, Result},
+    protocol::{
+        M1Message, M2Message, M3Message, M4Message, M5Message, M6Message, M7Message, SessionHandle,
+    },
+    suite::{CcaPke, Pke, Sig, Suite},
+};
+
+pub const PROTOCOL_VERSION: u8 = 1;
+pub const HEADER_LENGTH: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MessageType {
+    M1 = 1,
+    M2 = 2,
+    M3 = 3,
+    M4 = 4,
+    M5 = 5,
+    M6 = 6,
+    M7 = 7,
+}
+
+impl TryFrom<u8> for MessageType {
+    type Error = AkeError;
+
+    fn try_from(tag: u8) -> Result<Self> {
+        match tag {
+            1 => Ok(MessageType::M1),
+            2 => Ok(MessageType::M2),
+            3 => Ok(MessageType::M3),
+            4 => Ok(MessageType::M4),
+            5 => Ok(MessageType::M5),
+            6 => Ok(MessageType::M6),
+            7 => Ok(MessageType::M7),
+            _ => Err(AkeError::Decoding("message type")),
+        }
+    }
+}
+
+// Returns (version, suite identifier, message type) without consuming the body
+pub fn peek_header(bytes: &[u8]) -> Result<(u8, u8, MessageType)> {
+    if bytes.len() < HEADER_LENGTH {
+        return Err(AkeError::Decoding("message header"));
+    }
+
+    Ok((bytes[0], bytes[1], MessageType::try_from(bytes[2])?))
+}
+
+fn header<S: Suite>(config: &S, message_type: MessageType) -> Vec<u8> {
+    vec![PROTOCOL_VERSION, config.suite_id(), message_type as u8]
+}
+
+pub(crate) fn put_field(out: &mut Vec<u8>, field: &[u8]) {
+    out.extend_from_slice(&(field.len() as u32).to_be_bytes());
+    out.extend_from_slice(field);
+}
+
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    // Reads fields from a headerless buffer, e.g. a key file body
+    pub(crate) fn from_bytes(bytes: &'a [u8]) -> Self {
+        Reader { bytes }
+    }
+
+    fn new<S: Suite>(config: &S, bytes: &'a [u8], expected: MessageType) -> Result<Self> {
+        let (version, suite, message_type) = peek_header(bytes)?;
+        if version != PROTOCOL_VERSION {
+            return Err(AkeError::Decoding("protocol version"));
+        }
+        if suite != config.suite_id() {
+            return Err(AkeError::Decoding("suite identifier"));
+        }
+        if message_type != expected {
+            return Err(AkeError::Decoding("message type"));
+        }
+
+        Ok(Reader {
+            bytes: &bytes[HEADER_LENGTH..],
+        })
+    }
+
+    pub(crate) fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < n {
+            return Err(AkeError::Decoding("truncated message"));
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+
+        Ok(head)
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn handle(&mut self) -> Result<SessionHandle> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.take(8)?);
+
+        Ok(SessionHandle::from_be_bytes(bytes))
+    }
+
+    pub(crate) fn field(&mut self) -> Result<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    pub(crate) fn finish(self) -> Result<()> {
+        if !self.bytes.is_empty() {
+            return Err(AkeError::Decoding("trailing bytes"));
+        }
+
+        Ok(())
+    }
+}
+
+pub fn encode_m1<S: Suite>(config: &S, m1: &M1Message) -> Vec<u8> {
+    let mut out = header(config, MessageType::M1);
+    put_field(&mut out, m1);
+
+    out
+}
+
+pub fn decode_m1<S: Suite>(config: &S, bytes: &[u8]) -> Result<M1Message> {
+    let mut reader = Reader::new(config, bytes, MessageType::M1)?;
+    let comm = reader.field()?.to_vec();
+    reader.finish()?;
+
+    Ok(comm)
+}
+
+pub fn encode_m2<S: Suite>(config: &S, m2: &M2Message<S>) -> Vec<u8> {
+    let ((cis, r, pk), signature2, handle) = m2;
+    let mut out = header(config, MessageType::M2);
+    out.extend_from_slice(&handle.to_be_bytes());
+    out.extend_from_slice(&(cis.len() as u32).to_be_bytes());
+    for ci in cis {
+        put_field(&mut out, &config.pke().ciphertext_to_bytes(ci));
+    }
+    put_field(&mut out, r);
+    put_field(&mut out, &config.ccapke().public_key_to_bytes(pk));
+    put_field(&mut out, &config.sig().signature_to_bytes(signature2));
+
+    out
+}
+
+pub fn decode_m2<S: Suite>(config: &S, bytes: &[u8]) -> Result<M2Message<S>> {
+    let mut reader = Reader::new(config, bytes, MessageType::M2)?;
+    let handle = reader.handle()?;
+    let l = reader.u32()?;
+    let mut cis = Vec::new();
+    for _ in 0..l {
+        cis.push(config.pke().ciphertext_from_bytes(reader.field()?)?);
+    }
+    let r = reader.field()?.to_vec();
+    let pk = config.ccapke().public_key_from_bytes(reader.field()?)?;
+    let signature2 = config.sig().signature_from_bytes(reader.field()?)?;
+    reader.finish()?;
+
+    Ok(((cis, r, pk), signature2, handle))
+}
+
+pub fn encode_m3<S: Suite>(config: &S, m3: &M3Message) -> Vec<u8> {
+    let (comm_s, handle) = m3;
+    let mut out = header(config, MessageType::M3);
+    out.extend_from_slice(&handle.to_be_bytes());
+    put_field(&mut out, comm_s);
+
+    out
+}
+
+pub fn decode_m3<S: Suite>(config: &S, bytes: &[u8]) -> Result<M3Message> {
+    let mut reader = Reader::new(config, bytes, MessageType::M3)?;
+    let handle = reader.handle()?;
+    let comm_s = reader.field()?.to_vec();
+    reader.finish()?;
+
+    Ok((comm_s, handle))
+}
+
+pub fn encode_m4<S: Suite>(config: &S, m4: &M4Message<S>) -> Vec<u8> {
+    let (r, signature4) = m4;
+    let mut out = header(config, MessageType::M4);
+    put_field(&mut out, r);
+    put_field(&mut out, &config.sig().signature_to_bytes(signature4));
+
+    out
+}
+
+pub fn decode_m4<S: Suite>(config: &S, bytes: &[u8]) -> Result<M4Message<S>> {
+    let mut reader = Reader::new(config, bytes, MessageType::M4)?;
+    let r = reader.field()?.to_vec();
+    let signature4 = config.sig().signature_from_bytes(reader.field()?)?;
+    reader.finish()?;
+
+    Ok((r, signature4))
+}
+
+pub fn encode_m5<S: Suite>(config: &S, m5: &M5Message<S>) -> Vec<u8> {
+    let (ctxi, (x, r), handle) = m5;
+    let mut out = header(config, MessageType::M5);
+    out.extend_from_slice(&handle.to_be_bytes());
+    put_field(&mut out, &config.ccapke().ciphertext_to_bytes(ctxi));
+    put_field(&mut out, x);
+    put_field(&mut out, r);
+
+    out
+}
+
+pub fn decode_m5<S: Suite>(config: &S, bytes: &[u8]) -> Result<M5Message<S>> {
+    let mut reader = Reader::new(config, bytes, MessageType::M5)?;
+    let handle = reader.handle()?;
+    let ctxi = config.ccapke().ciphertext_from_bytes(reader.field()?)?;
+    let x = reader.field()?.to_vec();
+    let r = reader.field()?.to_vec();
+    reader.finish()?;
+
+    Ok((ctxi, (x, r), handle))
+}
+
+pub fn encode_m6<S: Suite>(config: &S, m6: &M6Message) -> Vec<u8> {
+    let mut out = header(config, MessageType::M6);
+    put_field(&mut out, m6);
+
+    out
+}
+
+pub fn decode_m6<S: Suite>(config: &S, bytes: &[u8]) -> Result<M6Message> {
+    let mut reader = Reader::new(config, bytes, MessageType::M6)?;
+    let tag = reader.field()?.to_vec();
+    reader.finish()?;
+
+    Ok(tag)
+}
+
+pub fn encode_m7<S: Suite>(config: &S, m7: &M7Message) -> Vec<u8> {
+    let (tag, handle) = m7;
+    let mut out = header(config, MessageType::M7);
+    out.extend_from_slice(&handle.to_be_bytes());
+    put_field(&mut out, tag);
+
+    out
+}
+
+pub fn decode_m7<S: Suite>(config: &S, bytes: &[u8]) -> Result<M7Message> {
+    let mut reader = Reader::new(config, bytes, MessageType::M7)?;
+    let handle = reader.handle()?;
+    let tag = reader.field()?.to_vec();
+    reader.finish()?;
+
+    Ok((tag, handle))
+}
@@ -0,0 +1,62 @@
+use rand::{CryptoRng, RngCore};
+
+use crate::common::{error::Result, suite::CcaPke, utils::get_random_bytes};
+use crate::pq::pke::DEM_NONCE_LENGTH;
+
+use super::{
+    pke::{
+        ciphertext_from_bytes, ciphertext_to_bytes, coins, decrypt, encrypt, key_gen,
+        public_key_from_bytes, public_key_to_bytes, HybridKem, PublicKey, SecretKey,
+    },
+    protocol::CiphertextType,
+};
+
+// The same KEM combiner as the PKE, with fresh coins for every encryption
+impl CcaPke for HybridKem {
+    type PublicKey = PublicKey;
+    type SecretKey = SecretKey;
+    type Ciphertext = CiphertextType;
+
+    fn algorithm_name(&self) -> String {
+        format!(
+            "ECIES-secp256k1+{}",
+            self.get_kem_algorithm().algorithm().name()
+        )
+    }
+
+    fn ccapke_gen<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Result<(PublicKey, SecretKey)> {
+        key_gen(self.get_kem_algorithm(), rng)
+    }
+
+    fn ccapke_enc<R: RngCore + CryptoRng>(
+        &self,
+        pk: &PublicKey,
+        m: &[u8],
+        rng: &mut R,
+    ) -> Result<CiphertextType> {
+        let kem = self.get_kem_algorithm();
+        let r = [coins(rng), get_random_bytes(rng, DEM_NONCE_LENGTH)].concat();
+
+        encrypt(kem, pk, m, &r)
+    }
+
+    fn ccapke_dec(&self, sk: &SecretKey, ct: &CiphertextType) -> Result<Vec<u8>> {
+        decrypt(self.get_kem_algorithm(), sk, ct, "CCA-PKE ciphertext")
+    }
+
+    fn public_key_to_bytes(&self, pk: &PublicKey) -> Vec<u8> {
+        public_key_to_bytes(pk)
+    }
+
+    fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<PublicKey> {
+        public_key_from_bytes(self.get_kem_algorithm(), bytes)
+    }
+
+    fn ciphertext_to_bytes(&self, ct: &CiphertextType) -> Vec<u8> {
+        ciphertext_to_bytes(ct)
+    }
+
+    fn ciphertext_from_bytes(&self, bytes: &[u8]) -> Result<CiphertextType> {
+        ciphertext_from_bytes(self.get_kem_algorithm(), bytes)
+    }
+}
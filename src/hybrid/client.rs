@@ -0,0 +1,5 @@
+use crate::common::client;
+
+use super::config::Config;
+
+pub type Client = client::Client<Config>;
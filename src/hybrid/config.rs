@@ -0,0 +1,95 @@
+use oqs::{kem, sig};
+use sha3::Sha3_256;
+
+use crate::common::{
+    error::Result,
+    prf::PrfAlgorithm,
+    suite::{Prf, Suite, PKE_COINS_LABEL},
+};
+use crate::pq::{commitment::Sha3Commitment, pke::DEM_NONCE_LENGTH};
+
+use super::{pke::HybridKem, sig::DualSig};
+
+pub const SUITE_ID: u8 = 0x03;
+
+pub struct Config {
+    users_numbers: u32,
+    kem: HybridKem,
+    sig: DualSig,
+    prf: PrfAlgorithm,
+}
+
+impl Config {
+    pub fn new(users_numbers: u32, kem: kem::Kem, sig: sig::Sig) -> Self {
+        Config {
+            users_numbers,
+            kem: HybridKem::new(kem),
+            sig: DualSig::new(sig),
+            prf: PrfAlgorithm::AesCtr,
+        }
+    }
+
+    // Both sides of a handshake must use the same PRF
+    pub fn set_prf_algorithm(&mut self, prf: PrfAlgorithm) {
+        self.prf = prf;
+    }
+
+    pub fn get_prf_algorithm(&self) -> PrfAlgorithm {
+        self.prf
+    }
+
+    pub fn get_kem_algorithm(&self) -> &kem::Kem {
+        self.kem.get_kem_algorithm()
+    }
+
+    pub fn get_signature_algorithm(&self) -> &sig::Sig {
+        self.sig.get_signature_algorithm()
+    }
+
+    pub fn get_users_number(&self) -> u32 {
+        self.users_numbers
+    }
+}
+
+impl Suite for Config {
+    type Pke = HybridKem;
+    type CcaPke = HybridKem;
+    type Sig = DualSig;
+    type Comm = Sha3Commitment;
+    type Prf = PrfAlgorithm;
+    type Hash = Sha3_256;
+
+    fn pke(&self) -> &HybridKem {
+        &self.kem
+    }
+
+    fn ccapke(&self) -> &HybridKem {
+        &self.kem
+    }
+
+    fn sig(&self) -> &DualSig {
+        &self.sig
+    }
+
+    fn comm(&self) -> &Sha3Commitment {
+        &Sha3Commitment
+    }
+
+    fn prf(&self) -> &PrfAlgorithm {
+        &self.prf
+    }
+
+    fn suite_id(&self) -> u8 {
+        SUITE_ID
+    }
+
+    fn get_users_number(&self) -> u32 {
+        self.users_numbers
+    }
+
+    // r_j <- PRF(r, j), as long as r plus the AES-GCM nonce, as in the PQ suite
+    fn pke_coins(&self, r: &[u8], j: u32) -> Result<Vec<u8>> {
+        self.prf()
+            .prf(r, PKE_COINS_LABEL, j as u64, r.len() + DEM_NONCE_LENGTH)
+    }
+}
@@ -0,0 +1,9 @@
+pub mod ccapke;
+pub mod client;
+pub mod config;
+pub mod pke;
+pub mod protocol;
+pub mod server;
+pub mod sig;
+pub mod store;
+pub mod vectors;
@@ -0,0 +1,249 @@
+// KEM combiner of ECIES over secp256k1 and a liboqs KEM. ECIES encrypts a
+// fresh 32-byte secret k_1, the KEM encapsulates k_2 and the AES-256-GCM key
+// of the DEM is
+//
+//   K := SHA3-256(enc("hybrid kem") || enc(k_1) || enc(k_2) || enc(ct_1) || enc(ct_2))
+//
+// so a message stays hidden as long as either ECIES or the KEM is secure.
+// The coins of the deterministic PKE are
+//
+//   r := ECIES coins (32) || k_1 (32) || KEM seed (32) || DEM nonce (12)
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use oqs::kem;
+use rand::{CryptoRng, RngCore};
+use sha3::{Digest, Sha3_256};
+use zeroize::Zeroizing;
+
+use crate::classic::pke as ecies;
+use crate::common::{
+    error::{AkeError, Result},
+    suite::Pke,
+    utils::{ct_eq, get_random_bytes},
+    wire::{put_field, Reader},
+};
+use crate::pq::{
+    pke::{encapsulate, keypair, DEM_NONCE_LENGTH},
+    protocol::TagType,
+    seeded::SEED_LENGTH,
+};
+
+use super::protocol::CiphertextType;
+
+pub const HYBRID_KEM_LABEL: &[u8] = b"hybrid kem";
+// Length of the ECIES coins and of k_1
+pub const ECIES_SECRET_LENGTH: usize = 32;
+
+pub type PublicKey = (libsecp256k1::PublicKey, kem::PublicKey);
+pub type SecretKey = (libsecp256k1::SecretKey, kem::SecretKey);
+
+pub struct HybridKem {
+    kem: kem::Kem,
+}
+
+impl HybridKem {
+    pub fn new(kem: kem::Kem) -> Self {
+        HybridKem { kem }
+    }
+
+    pub fn get_kem_algorithm(&self) -> &kem::Kem {
+        &self.kem
+    }
+}
+
+fn combine(k1: &[u8], k2: &[u8], ct1: &[u8], ct2: &[u8]) -> Zeroizing<Vec<u8>> {
+    let mut input = Zeroizing::new(Vec::new());
+    for field in [HYBRID_KEM_LABEL, k1, k2, ct1, ct2] {
+        put_field(&mut input, field);
+    }
+
+    let mut hasher = Sha3_256::new();
+    hasher.update(input.as_slice());
+    Zeroizing::new(hasher.finalize().to_vec())
+}
+
+pub fn key_gen<R: RngCore + CryptoRng>(
+    kem: &kem::Kem,
+    rng: &mut R,
+) -> Result<(PublicKey, SecretKey)> {
+    let (pk1, sk1) = ecies::pke_gen(rng);
+    let (pk2, sk2) = keypair(kem, rng)?;
+
+    Ok(((pk1, pk2), (sk1, sk2)))
+}
+
+pub fn encrypt(kem: &kem::Kem, pk: &PublicKey, m: &[u8], r: &[u8]) -> Result<CiphertextType> {
+    if r.len() != 2 * ECIES_SECRET_LENGTH + SEED_LENGTH + DEM_NONCE_LENGTH {
+        return Err(AkeError::Decoding("PKE coins"));
+    }
+    let (ecies_coins, rest) = r.split_at(ECIES_SECRET_LENGTH);
+    let (k1, rest) = rest.split_at(ECIES_SECRET_LENGTH);
+    let (seed, nonce) = rest.split_at(SEED_LENGTH);
+
+    let ct1 = ecies::pke_enc(&pk.0, k1, ecies_coins)?;
+    let (ct2, k2) = encapsulate(kem, &pk.1, seed)?;
+    let k2 = Zeroizing::new(k2.into_vec());
+    let key = combine(k1, &k2, &ct1, ct2.as_ref());
+
+    let cipher =
+        Aes256Gcm::new_from_slice(&key).map_err(|_| AkeError::Primitive("AES-GCM key setup"))?;
+    let iv = Nonce::from_slice(nonce);
+    let ciphertext = cipher
+        .encrypt(iv, m)
+        .map_err(|_| AkeError::Primitive("AES-GCM encryption"))?;
+
+    Ok((ct1, ct2, ciphertext, *iv))
+}
+
+// `what` names the ciphertext in the error when it does not decrypt
+pub fn decrypt(
+    kem: &kem::Kem,
+    sk: &SecretKey,
+    ct: &CiphertextType,
+    what: &'static str,
+) -> Result<Vec<u8>> {
+    let (ct1, ct2, ciphertext, iv) = ct;
+    let k1 = Zeroizing::new(ecies::pke_dec(&sk.0, ct1).map_err(|_| AkeError::Verification(what))?);
+    let k2 = kem
+        .decapsulate(&sk.1, ct2)
+        .map_err(|_| AkeError::Primitive("KEM decapsulation"))?;
+    let k2 = Zeroizing::new(k2.into_vec());
+    let key = combine(&k1, &k2, ct1, ct2.as_ref());
+
+    let cipher =
+        Aes256Gcm::new_from_slice(&key).map_err(|_| AkeError::Primitive("AES-GCM key setup"))?;
+    cipher
+        .decrypt(iv, ciphertext.as_slice())
+        .map_err(|_| AkeError::Verification(what))
+}
+
+pub fn check_ciphertext(c1: &CiphertextType, c2: &CiphertextType) -> bool {
+    // Every part is compared, whether or not an earlier one differs
+    let are_equal0: bool = ct_eq(&c1.0, &c2.0);
+    let are_equal1: bool = ct_eq(c1.1.as_ref(), c2.1.as_ref());
+    let are_equal2: bool = ct_eq(&c1.2, &c2.2);
+    let are_equal3: bool = ct_eq(&c1.3, &c2.3);
+
+    are_equal0 & are_equal1 & are_equal2 & are_equal3
+}
+
+// Random coins of `encrypt` without the DEM nonce, see `Config::pke_coins`
+pub fn coins<R: RngCore + CryptoRng>(rng: &mut R) -> Vec<u8> {
+    get_random_bytes(rng, 2 * ECIES_SECRET_LENGTH + SEED_LENGTH)
+}
+
+// enc(ECIES public key) || enc(KEM public key)
+pub fn public_key_to_bytes(pk: &PublicKey) -> Vec<u8> {
+    let mut out = Vec::new();
+    put_field(&mut out, &pk.0.serialize());
+    put_field(&mut out, pk.1.as_ref());
+    out
+}
+
+pub fn public_key_from_bytes(kem: &kem::Kem, bytes: &[u8]) -> Result<PublicKey> {
+    let mut reader = Reader::from_bytes(bytes);
+    let pk1 = libsecp256k1::PublicKey::parse_slice(reader.field()?, None)
+        .map_err(|_| AkeError::Decoding("ECIES public key"))?;
+    let pk2 = kem
+        .public_key_from_bytes(reader.field()?)
+        .map(|pk| pk.to_owned())
+        .ok_or(AkeError::Decoding("KEM public key"))?;
+    reader.finish()?;
+
+    Ok((pk1, pk2))
+}
+
+// enc(ECIES ciphertext) || KEM ciphertext || enc(DEM ciphertext) || nonce
+pub fn ciphertext_to_bytes(ct: &CiphertextType) -> Vec<u8> {
+    let mut out = Vec::new();
+    put_field(&mut out, &ct.0);
+    out.extend_from_slice(ct.1.as_ref());
+    put_field(&mut out, &ct.2);
+    out.extend_from_slice(&ct.3);
+    out
+}
+
+pub fn ciphertext_from_bytes(kem: &kem::Kem, bytes: &[u8]) -> Result<CiphertextType> {
+    let mut reader = Reader::from_bytes(bytes);
+    let ct1 = reader.field()?.to_vec();
+    let ct2 = kem
+        .ciphertext_from_bytes(reader.take(kem.length_ciphertext())?)
+        .ok_or(AkeError::Decoding("KEM ciphertext"))?
+        .to_owned();
+    let ct_dem = reader.field()?.to_vec();
+    let iv = *TagType::from_slice(reader.take(DEM_NONCE_LENGTH)?);
+    reader.finish()?;
+
+    Ok((ct1, ct2, ct_dem, iv))
+}
+
+impl Pke for HybridKem {
+    type PublicKey = PublicKey;
+    type SecretKey = SecretKey;
+    type Ciphertext = CiphertextType;
+
+    fn algorithm_name(&self) -> String {
+        format!("ECIES-secp256k1+{}", self.kem.algorithm().name())
+    }
+
+    fn pke_gen<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Result<(PublicKey, SecretKey)> {
+        key_gen(&self.kem, rng)
+    }
+
+    fn pke_enc(&self, pk: &PublicKey, m: &[u8], r: &[u8]) -> Result<CiphertextType> {
+        encrypt(&self.kem, pk, m, r)
+    }
+
+    fn pke_dec(&self, sk: &SecretKey, ct: &CiphertextType) -> Result<Vec<u8>> {
+        decrypt(&self.kem, sk, ct, "PKE ciphertext")
+    }
+
+    fn check_ciphertext(&self, c1: &CiphertextType, c2: &CiphertextType) -> bool {
+        check_ciphertext(c1, c2)
+    }
+
+    fn pke_randomness<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Result<Vec<u8>> {
+        Ok(coins(rng))
+    }
+
+    fn public_key_to_bytes(&self, pk: &PublicKey) -> Vec<u8> {
+        public_key_to_bytes(pk)
+    }
+
+    fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<PublicKey> {
+        public_key_from_bytes(&self.kem, bytes)
+    }
+
+    // enc(ECIES secret key) || enc(KEM secret key)
+    fn secret_key_to_bytes(&self, sk: &SecretKey) -> Vec<u8> {
+        let mut out = Vec::new();
+        put_field(&mut out, &sk.0.serialize());
+        put_field(&mut out, sk.1.as_ref());
+        out
+    }
+
+    fn secret_key_from_bytes(&self, bytes: &[u8]) -> Result<SecretKey> {
+        let mut reader = Reader::from_bytes(bytes);
+        let sk1 = libsecp256k1::SecretKey::parse_slice(reader.field()?)
+            .map_err(|_| AkeError::Decoding("ECIES secret key"))?;
+        let sk2 = self
+            .kem
+            .secret_key_from_bytes(reader.field()?)
+            .map(|sk| sk.to_owned())
+            .ok_or(AkeError::Decoding("KEM secret key"))?;
+        reader.finish()?;
+
+        Ok((sk1, sk2))
+    }
+
+    fn ciphertext_to_bytes(&self, ct: &CiphertextType) -> Vec<u8> {
+        ciphertext_to_bytes(ct)
+    }
+
+    fn ciphertext_from_bytes(&self, bytes: &[u8]) -> Result<CiphertextType> {
+        ciphertext_from_bytes(&self.kem, bytes)
+    }
+}
@@ -0,0 +1,15 @@
+use crate::common::protocol;
+use crate::pq::protocol::TagType;
+
+use super::config::Config;
+
+pub use crate::common::protocol::{
+    get_m1_length, get_m2_length, get_m3_length, get_m4_length, get_m5_length, registration,
+    round_1, round_2, round_3, round_4, round_5, round_6, round_7, round_8, round_9, show_diagram,
+};
+
+// (ECIES ciphertext of k_1, KEM ciphertext, DEM ciphertext, DEM nonce)
+pub type CiphertextType = (Vec<u8>, oqs::kem::Ciphertext, Vec<u8>, TagType);
+pub type M2Message = protocol::M2Message<Config>;
+pub type M4Message = protocol::M4Message<Config>;
+pub type M5Message = protocol::M5Message<Config>;
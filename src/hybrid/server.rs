@@ -0,0 +1,5 @@
+use crate::common::server;
+
+use super::config::Config;
+
+pub type Server = server::Server<Config>;
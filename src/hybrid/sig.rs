@@ -0,0 +1,127 @@
+// Dual signature: σ := (σ_ECDSA, σ_PQ) on the same message, valid only if
+// both verify, so forging it needs breaking ECDSA over secp256k1 and the
+// liboqs signature
+
+use oqs::sig;
+use rand::{CryptoRng, RngCore};
+
+use crate::classic::sig::Ecdsa;
+use crate::common::{
+    error::Result,
+    suite::Sig,
+    wire::{put_field, Reader},
+};
+
+pub type PublicKey = (k256::PublicKey, sig::PublicKey);
+pub type SecretKey = (k256::SecretKey, sig::SecretKey);
+pub type Signature = (k256::ecdsa::Signature, sig::Signature);
+
+pub struct DualSig {
+    sig: sig::Sig,
+}
+
+impl DualSig {
+    pub fn new(sig: sig::Sig) -> Self {
+        DualSig { sig }
+    }
+
+    pub fn get_signature_algorithm(&self) -> &sig::Sig {
+        &self.sig
+    }
+}
+
+fn pair_to_bytes(first: &[u8], second: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    put_field(&mut out, first);
+    put_field(&mut out, second);
+    out
+}
+
+fn pair_from_bytes(bytes: &[u8]) -> Result<(&[u8], &[u8])> {
+    let mut reader = Reader::from_bytes(bytes);
+    let first = reader.field()?;
+    let second = reader.field()?;
+    reader.finish()?;
+
+    Ok((first, second))
+}
+
+impl Sig for DualSig {
+    type PublicKey = PublicKey;
+    type SecretKey = SecretKey;
+    type Signature = Signature;
+
+    fn algorithm_name(&self) -> String {
+        format!("ECDSA-secp256k1+{}", self.sig.algorithm().name())
+    }
+
+    fn sig_gen<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Result<(PublicKey, SecretKey)> {
+        let (pk1, sk1) = Ecdsa.sig_gen(rng)?;
+        let (pk2, sk2) = Sig::sig_gen(&self.sig, rng)?;
+
+        Ok(((pk1, pk2), (sk1, sk2)))
+    }
+
+    fn sig_sign(&self, sk: &SecretKey, m: &[u8]) -> Result<Signature> {
+        let signature1 = Ecdsa.sig_sign(&sk.0, m)?;
+        let signature2 = Sig::sig_sign(&self.sig, &sk.1, m)?;
+
+        Ok((signature1, signature2))
+    }
+
+    fn sig_vry(&self, pk: &PublicKey, m: &[u8], signature: &Signature) -> bool {
+        let verification1 = Ecdsa.sig_vry(&pk.0, m, &signature.0);
+        let verification2 = Sig::sig_vry(&self.sig, &pk.1, m, &signature.1);
+
+        verification1 && verification2
+    }
+
+    // enc(ECDSA part) || enc(liboqs part), for keys and signatures alike
+    fn public_key_to_bytes(&self, pk: &PublicKey) -> Vec<u8> {
+        pair_to_bytes(
+            &Ecdsa.public_key_to_bytes(&pk.0),
+            &Sig::public_key_to_bytes(&self.sig, &pk.1),
+        )
+    }
+
+    fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<PublicKey> {
+        let (pk1, pk2) = pair_from_bytes(bytes)?;
+
+        Ok((
+            Ecdsa.public_key_from_bytes(pk1)?,
+            Sig::public_key_from_bytes(&self.sig, pk2)?,
+        ))
+    }
+
+    fn secret_key_to_bytes(&self, sk: &SecretKey) -> Vec<u8> {
+        pair_to_bytes(
+            &Ecdsa.secret_key_to_bytes(&sk.0),
+            &Sig::secret_key_to_bytes(&self.sig, &sk.1),
+        )
+    }
+
+    fn secret_key_from_bytes(&self, bytes: &[u8]) -> Result<SecretKey> {
+        let (sk1, sk2) = pair_from_bytes(bytes)?;
+
+        Ok((
+            Ecdsa.secret_key_from_bytes(sk1)?,
+            Sig::secret_key_from_bytes(&self.sig, sk2)?,
+        ))
+    }
+
+    fn signature_to_bytes(&self, signature: &Signature) -> Vec<u8> {
+        pair_to_bytes(
+            &Ecdsa.signature_to_bytes(&signature.0),
+            &Sig::signature_to_bytes(&self.sig, &signature.1),
+        )
+    }
+
+    fn signature_from_bytes(&self, bytes: &[u8]) -> Result<Signature> {
+        let (signature1, signature2) = pair_from_bytes(bytes)?;
+
+        Ok((
+            Ecdsa.signature_from_bytes(signature1)?,
+            Sig::signature_from_bytes(&self.sig, signature2)?,
+        ))
+    }
+}
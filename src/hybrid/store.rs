@@ -0,0 +1,5 @@
+pub use crate::common::store::{
+    client_keygen_to_file, load_client, load_client_keys, load_client_public_key, load_registry,
+    load_server, load_server_keys, load_server_public_key, public_key_path, register_client,
+    save_client_keys, server_keygen,
+};
@@ -0,0 +1,42 @@
+// Known-answer vectors of the hybrid suite, see `common::vectors`. Suite names
+// read "ECIES-secp256k1+<KEM>+ECDSA-secp256k1+<signature scheme>".
+
+use super::config::Config;
+use crate::common::{
+    error::{AkeError, Result},
+    vectors::{check_vector, check_vectors, generate_vectors, vector_parameters, TestVector},
+};
+use crate::pq::vectors::{kem_named, split_signature};
+
+pub use crate::common::vectors::{read_vectors, write_vectors};
+
+// Shipped with the crate and checked by `cargo test`
+pub const VECTORS_FILE: &str = "vectors/hybrid.json";
+
+// Configuration a vector was generated with
+pub fn vector_config(vector: &TestVector) -> Result<Config> {
+    let (users, prf) = vector_parameters(vector)?;
+    let (pke, sig) = split_signature(&vector.suite)?;
+    let kem = pke
+        .strip_prefix("ECIES-secp256k1+")
+        .and_then(|pke| pke.strip_suffix("+ECDSA-secp256k1"))
+        .ok_or(AkeError::Decoding("test vector: suite"))?;
+
+    let mut config = Config::new(users, kem_named(kem)?, sig);
+    config.set_prf_algorithm(prf);
+
+    Ok(config)
+}
+
+pub fn check(vector: &TestVector) -> Result<()> {
+    check_vector(&vector_config(vector)?, vector)
+}
+
+pub fn generate(config: &Config, number: u64) -> Result<Vec<TestVector>> {
+    generate_vectors(config, number)
+}
+
+// Checks every vector and prints one line per seed. False if any fails.
+pub fn check_all(vectors: &[TestVector]) -> bool {
+    check_vectors(vectors, check)
+}
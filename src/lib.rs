@@ -1,3 +1,4 @@
 pub mod classic;
 pub mod common;
+pub mod hybrid;
 pub mod pq;
@@ -0,0 +1,445 @@
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, Instant};
+
+use clap::{Parser, Subcommand};
+use rand::rngs::OsRng;
+
+use generic_anon_ake::common::error::AkeError;
+use generic_anon_ake::common::net::{connect_session, serve};
+use generic_anon_ake::common::prf::{print_supported_prfs, PrfAlgorithm};
+use generic_anon_ake::common::utils::{exit_on_error, print_hex, required};
+use generic_anon_ake::hybrid::config::Config;
+use generic_anon_ake::hybrid::protocol::{
+    get_m1_length, get_m2_length, get_m3_length, get_m4_length, get_m5_length, registration,
+    round_1, round_2, round_3, round_4, round_5, round_6, round_7, round_8, round_9, show_diagram,
+};
+use generic_anon_ake::hybrid::store::{
+    client_keygen_to_file, load_client, load_client_public_key, load_server, public_key_path,
+    register_client, server_keygen,
+};
+use generic_anon_ake::hybrid::vectors::{self, read_vectors, write_vectors};
+use generic_anon_ake::pq::supported_algs::{
+    get_kem_algorithm, get_signature_algorithm, print_supported_kems, print_supported_signatures,
+};
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// KEM, e.g. ML-KEM-768 (FIPS 203) or Kyber768 (round 3). Not needed to
+    /// check vectors
+    #[arg(short, long)]
+    kem: Option<String>,
+
+    /// Signature scheme, e.g. ML-DSA-65 (FIPS 204) or Dilithium3 (round 3).
+    /// Not needed to check vectors
+    #[arg(short, long)]
+    sig: Option<String>,
+
+    /// Number of clients. Not needed to check vectors
+    #[arg(short, long)]
+    #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+    clients: Option<u32>,
+
+    /// PRF used to derive the coins of round 2 (AES-CTR, SHAKE256 or KMAC256)
+    #[arg(long, default_value = "AES-CTR")]
+    prf: String,
+
+    /// Index of the client that runs the handshake in local mode
+    #[arg(short, long, default_value_t = 0)]
+    index: u32,
+
+    /// Run the optional key confirmation (rounds 7 to 9) after round 6
+    #[arg(long, default_value_t = false)]
+    confirm: bool,
+
+    #[arg(short, long, default_value_t = false)]
+    verbose: bool,
+
+    #[command(subcommand)]
+    mode: Option<Mode>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Mode {
+    /// Generate a client key pair locally (the public key goes to <KEY>.pub)
+    Keygen {
+        #[arg(long)]
+        key: PathBuf,
+    },
+    /// Generate the server signing key pair (the public key goes to <STATE>/server.pub)
+    ServerKeygen {
+        /// Directory with the server signing key and the client registry
+        #[arg(long, default_value = "server-state")]
+        state: PathBuf,
+    },
+    /// Add a client public key to the server registry
+    Register {
+        /// Directory with the server signing key and the client registry
+        #[arg(long, default_value = "server-state")]
+        state: PathBuf,
+
+        /// Client public key file written by `keygen`
+        #[arg(long)]
+        public_key: PathBuf,
+    },
+    /// Listen on a TCP address and run the server side of the handshake
+    Serve {
+        #[arg(short, long, default_value = "127.0.0.1:4000")]
+        address: String,
+
+        /// Directory with the server signing key and the client registry
+        #[arg(long, default_value = "server-state")]
+        state: PathBuf,
+    },
+    /// Run the client side of the handshake against a server
+    Connect {
+        #[arg(short, long, default_value = "127.0.0.1:4000")]
+        address: String,
+
+        /// Client key file written by `keygen`
+        #[arg(long)]
+        key: PathBuf,
+
+        /// Directory with the public server key and client registry
+        #[arg(long, default_value = "server-state")]
+        state: PathBuf,
+    },
+    /// Check the handshake against known-answer vectors, or regenerate them
+    ///
+    /// Each vector names its own suite, so checking needs no --kem, --sig or
+    /// --clients; writing takes the suite from them.
+    Vectors {
+        #[arg(long, default_value = vectors::VECTORS_FILE)]
+        file: PathBuf,
+
+        /// Write fresh vectors for seeds 0, 1, ... instead of checking
+        #[arg(long, default_value_t = false)]
+        write: bool,
+    },
+}
+
+// Number of vectors written by `vectors --write`
+const VECTORS_NUMBER: u64 = 4;
+
+fn main() {
+    let args = Args::parse();
+    let verbose = args.verbose;
+    let confirm = args.confirm;
+
+    if let Some(Mode::Vectors { file, write: false }) = &args.mode {
+        check_vectors(file);
+        return;
+    }
+
+    // Init
+    let users = required(args.clients, "--clients");
+    let sig = required(args.sig, "--sig");
+    let kem = required(args.kem, "--kem");
+
+    // Init the PQ half of the dual signature
+    println!("[!] Setting ECDSA-secp256k1+{sig} as signature scheme...");
+    let sigalg = match get_signature_algorithm(&sig) {
+        Ok(sigalg) => sigalg,
+        Err(e) => {
+            println!(
+                "[!] Signature {sig} is invalid or is not supported ({e})!\n[!] Suppored signature schemes:"
+            );
+            print_supported_signatures();
+            process::exit(1);
+        }
+    };
+
+    // Init the PQ half of the KEM combiner
+    println!("[!] Setting ECIES-secp256k1+{kem} as KEM...\n");
+    let kemalg = match get_kem_algorithm(&kem) {
+        Ok(kemalg) => kemalg,
+        Err(e) => {
+            println!("[!] Kem {kem} is invalid or is not supported ({e})!\n[!] Suppored KEMS:");
+            print_supported_kems();
+            process::exit(1);
+        }
+    };
+
+    let prf = PrfAlgorithm::from_name(&args.prf);
+    if prf.is_none() {
+        println!(
+            "[!] PRF {} is invalid or is not supported!\n[!] Supported PRFs:",
+            args.prf
+        );
+        print_supported_prfs();
+        process::exit(1);
+    }
+
+    let mut config: Config = Config::new(users, kemalg, sigalg);
+    config.set_prf_algorithm(prf.unwrap());
+
+    match args.mode {
+        Some(Mode::Keygen { key }) => run_keygen(&config, &key),
+        Some(Mode::ServerKeygen { state }) => run_server_keygen(&config, &state),
+        Some(Mode::Register { state, public_key }) => run_register(&config, &state, &public_key),
+        Some(Mode::Serve { address, state }) => {
+            run_server(&config, &address, &state, confirm, verbose)
+        }
+        Some(Mode::Connect {
+            address,
+            key,
+            state,
+        }) => run_client(&config, &address, &key, &state, confirm, verbose),
+        Some(Mode::Vectors { file, .. }) => write_test_vectors(&config, &file),
+        None => run_local(&config, users, args.index, confirm, verbose),
+    }
+}
+
+fn run_keygen(config: &Config, key: &Path) {
+    exit_on_error(client_keygen_to_file(config, key), "Key generation");
+    println!(
+        "[C] Key pair written to {}, submit {} for registration",
+        key.display(),
+        public_key_path(key).display()
+    );
+}
+
+fn run_server_keygen(config: &Config, state: &Path) {
+    exit_on_error(server_keygen(config, state), "Server key generation");
+    println!(
+        "[S] Signing key pair written to {}, clients pin {}",
+        state.join("server.key").display(),
+        state.join("server.pub").display()
+    );
+}
+
+fn run_register(config: &Config, state: &Path, public_key: &Path) {
+    let vk = exit_on_error(
+        load_client_public_key(config, public_key),
+        "Loading the client public key",
+    );
+    let id = exit_on_error(register_client(config, state, &vk), "Registration");
+    println!("[S] Registered {} as client {id}", public_key.display());
+}
+
+fn run_server(config: &Config, address: &str, state: &Path, confirm: bool, verbose: bool) {
+    let mut server = exit_on_error(load_server(config, state), "Loading the server state");
+
+    let listener = exit_on_error(
+        TcpListener::bind(address).map_err(AkeError::from),
+        "Listening",
+    );
+    println!("[!] Listening on {address}...\n");
+
+    exit_on_error(
+        serve(&mut server, config, &listener, confirm, verbose),
+        "Serving",
+    );
+}
+
+fn run_client(
+    config: &Config,
+    address: &str,
+    key: &Path,
+    state: &Path,
+    confirm: bool,
+    verbose: bool,
+) {
+    let mut client = exit_on_error(load_client(config, key, state), "Loading the client key");
+    let mut stream = exit_on_error(
+        TcpStream::connect(address).map_err(AkeError::from),
+        "Connecting",
+    );
+    if verbose {
+        println!("[C] Connected to {address} as client {}", client.get_id());
+    }
+
+    exit_on_error(
+        connect_session(
+            &mut client,
+            config,
+            &mut stream,
+            &mut OsRng,
+            confirm,
+            verbose,
+        ),
+        "Handshake",
+    );
+
+    println!("[!] Printing session key and identifier...");
+    print_hex(
+        exit_on_error(client.get_key(), "Reading the session key"),
+        "[C] key",
+    );
+    print_hex(
+        exit_on_error(client.get_sid(), "Reading the session identifier"),
+        "[C] sid",
+    );
+    if client.is_confirmed() {
+        println!("[C] Session confirmed by the server");
+    }
+}
+
+fn check_vectors(file: &Path) {
+    let vectors = exit_on_error(read_vectors(file), "Reading the test vectors");
+    if !vectors::check_all(&vectors) {
+        process::exit(1);
+    }
+}
+
+fn write_test_vectors(config: &Config, file: &Path) {
+    let vectors = exit_on_error(
+        vectors::generate(config, VECTORS_NUMBER),
+        "Generating the test vectors",
+    );
+    exit_on_error(write_vectors(file, &vectors), "Writing the test vectors");
+    println!(
+        "[!] {} test vectors written to {}",
+        vectors.len(),
+        file.display()
+    );
+}
+
+fn run_local(config: &Config, users: u32, index: u32, confirm: bool, verbose: bool) {
+    if index >= users {
+        println!("[!] Client index {index} is out of range for {users} clients!");
+        process::exit(1);
+    }
+
+    let mut rng = OsRng;
+    let mut times: Vec<Duration> = Vec::new();
+    let mut lengths: Vec<usize> = Vec::new();
+
+    if verbose {
+        println!("[!] Creating {users} clients...");
+    }
+
+    if verbose {
+        println!("[!] Creating server...\n");
+    }
+
+    if verbose {
+        println!("[R] Creating (ek, vk) for {users} clients...\n");
+    }
+    let start = Instant::now();
+    let (mut server, mut clients) = exit_on_error(registration(config, &mut rng), "Registration");
+    let mut client = clients.swap_remove(index as usize);
+    let duration = start.elapsed();
+    println!("[!] Time elapsed in registration of {users} clients is {duration:?}\n");
+    times.push(duration);
+
+    if verbose {
+        println!("[!] Starting protocol with client {index} and server...\n");
+        println!("[C] Running Round 1...");
+    }
+    let start = Instant::now();
+    let m1 = exit_on_error(round_1(&mut client, config, &mut rng), "Round 1");
+    lengths.push(get_m1_length(config, &m1));
+    let duration = start.elapsed();
+    println!("[!] Time elapsed in Round 1 is {duration:?}");
+    times.push(duration);
+
+    if verbose {
+        println!("[C -> S] Sending m1 to server...\n");
+    }
+    let handle = client.send_m1(m1, &mut server);
+
+    if verbose {
+        println!("[S] Running Round 2...");
+    }
+    let start = Instant::now();
+    let m2 = exit_on_error(round_2(&mut server, config, handle, &mut rng), "Round 2");
+    lengths.push(get_m2_length(config, &m2));
+    let duration = start.elapsed();
+    println!("[!] Time elapsed in Round 2 is {duration:?}");
+    times.push(duration);
+
+    if verbose {
+        println!("[C <- S] Sending m2 to client...\n");
+    }
+    server.send_m2(m2, &mut client);
+
+    if verbose {
+        println!("[C] Running Round 3...");
+    }
+    let start = Instant::now();
+    let m3 = exit_on_error(round_3(&mut client, config, &mut rng, verbose), "Round 3");
+    lengths.push(get_m3_length(config, &m3));
+    let duration = start.elapsed();
+    println!("[!] Time elapsed in Round 3 is {duration:?}");
+    times.push(duration);
+    if verbose {
+        println!("[C -> S] Sending m3 to server...\n");
+    }
+    client.send_m3(m3, &mut server);
+
+    if verbose {
+        println!("[S] Running Round 4...");
+    }
+    let start = Instant::now();
+    let m4 = exit_on_error(round_4(&mut server, config, handle), "Round 4");
+    lengths.push(get_m4_length(config, &m4));
+    let duration = start.elapsed();
+    println!("[!] Time elapsed in Round 4 is {duration:?}");
+    times.push(duration);
+    if verbose {
+        println!("[C <- S] Sending m4 to client...\n");
+    }
+    server.send_m4(m4, &mut client);
+
+    if verbose {
+        println!("[C] Running Round 5...");
+    }
+    let start = Instant::now();
+    let m5 = exit_on_error(round_5(&mut client, config, &mut rng, verbose), "Round 5");
+    lengths.push(get_m5_length(config, &m5));
+    let duration = start.elapsed();
+    times.push(duration);
+    println!("[!] Time elapsed in Round 5 is {duration:?}");
+    if verbose {
+        println!("[C -> S] Sending m5 to server...\n");
+    }
+    client.send_m5(m5, &mut server);
+
+    if verbose {
+        println!("[S] Running Round 6...");
+    }
+    let start = Instant::now();
+    exit_on_error(round_6(&mut server, config, handle, verbose), "Round 6");
+    let duration = start.elapsed();
+    times.push(duration);
+    println!("[!] Time elapsed in Round 6 is {duration:?}\n");
+
+    if confirm {
+        if verbose {
+            println!("[S] Running Round 7...");
+        }
+        let m6 = exit_on_error(round_7(&mut server, config, handle), "Round 7");
+        if verbose {
+            println!("[C <- S] Sending m6 to client...\n");
+            println!("[C] Running Round 8...");
+        }
+        server.send_m6(m6, &mut client);
+        let m7 = exit_on_error(round_8(&mut client, config, verbose), "Round 8");
+        if verbose {
+            println!("[C -> S] Sending m7 to server...\n");
+            println!("[S] Running Round 9...");
+        }
+        client.send_m7(m7, &mut server);
+        exit_on_error(round_9(&mut server, config, handle, verbose), "Round 9");
+        println!("[!] Key confirmation -> OK\n");
+    }
+
+    println!("[!] Printing session keys...");
+    let key_server = exit_on_error(server.get_key(handle), "Reading the session key");
+    let key_client = exit_on_error(client.get_key(), "Reading the session key");
+    print_hex(key_client, "[C]");
+    print_hex(key_server, "[S]");
+    println!();
+    println!("[!] Printing session identifiers...");
+    let sid_server = exit_on_error(server.get_sid(handle), "Reading the session identifier");
+    let sid_client = exit_on_error(client.get_sid(), "Reading the session identifier");
+    print_hex(sid_client, "[C]");
+    print_hex(sid_server, "[S]");
+    println!();
+    exit_on_error(server.finish_session(handle), "Finishing the session");
+    println!("[!] Printing diagram...");
+    show_diagram(config, &times, &lengths);
+}
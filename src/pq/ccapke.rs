@@ -1,39 +1,98 @@
-use aes_gcm::{
-    aead::{Aead, KeyInit},
-    Aes256Gcm, Nonce,
-};
+use aes_gcm::{aead::Aead, Nonce};
 
 use oqs::kem::{self, Ciphertext};
+use rand::{CryptoRng, RngCore};
 
-use crate::common::utils::get_nonce;
+use crate::common::{
+    error::{AkeError, Result},
+    suite::CcaPke,
+    utils::get_random_bytes,
+};
 
-use super::protocol::TagType;
+use super::{
+    pke::{ciphertext_from_bytes, dem_cipher, encapsulate, keypair, DEM_NONCE_LENGTH},
+    protocol::{CiphertextType, TagType},
+    seeded::seed_from_rng,
+};
 
-pub fn ccapke_enc(
+pub fn ccapke_enc<R: RngCore + CryptoRng>(
     kem: &kem::Kem,
     pk: &kem::PublicKey,
-    m: &Vec<u8>,
-) -> (Ciphertext, Vec<u8>, TagType) {
-    let r = kem.get_randomness().unwrap();
-    let (ct, k) = kem.encapsulate(pk, &r).unwrap();
-    let cipher = Aes256Gcm::new_from_slice(k.into_vec().as_slice()).unwrap();
-    let nonce = get_nonce();
+    m: &[u8],
+    rng: &mut R,
+) -> Result<(Ciphertext, Vec<u8>, TagType)> {
+    let (ct, k) = encapsulate(kem, pk, &seed_from_rng(rng))?;
+    let cipher = dem_cipher(k.as_ref())?;
+    let nonce = get_random_bytes(rng, DEM_NONCE_LENGTH);
     let iv = Nonce::from_slice(nonce.as_slice());
-    let ciphertext = cipher.encrypt(iv, m.as_slice()).unwrap();
+    let ciphertext = cipher
+        .encrypt(iv, m)
+        .map_err(|_| AkeError::Primitive("AES-GCM encryption"))?;
 
-    (ct, ciphertext, *iv)
+    Ok((ct, ciphertext, *iv))
 }
 
 pub fn ccapke_dec(
     kem: &kem::Kem,
-    sk: kem::SecretKey,
+    sk: &kem::SecretKey,
     ct: &Ciphertext,
-    ciphertext: &Vec<u8>,
+    ciphertext: &[u8],
     iv: &TagType,
-) -> Vec<u8> {
-    let k = kem.decapsulate(&sk, ct).unwrap();
-    let cipher = Aes256Gcm::new_from_slice(k.into_vec().as_slice()).unwrap();
-    let plaintext = cipher.decrypt(iv, ciphertext.as_ref()).unwrap();
+) -> Result<Vec<u8>> {
+    let k = kem
+        .decapsulate(sk, ct)
+        .map_err(|_| AkeError::Primitive("KEM decapsulation"))?;
+    let cipher = dem_cipher(k.as_ref())?;
+    cipher
+        .decrypt(iv, ciphertext)
+        .map_err(|_| AkeError::Verification("CCA-PKE ciphertext"))
+}
+
+impl CcaPke for kem::Kem {
+    type PublicKey = kem::PublicKey;
+    type SecretKey = kem::SecretKey;
+    type Ciphertext = CiphertextType;
+
+    fn algorithm_name(&self) -> String {
+        self.algorithm().name().to_string()
+    }
+
+    fn ccapke_gen<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(kem::PublicKey, kem::SecretKey)> {
+        keypair(self, rng)
+    }
+
+    fn ccapke_enc<R: RngCore + CryptoRng>(
+        &self,
+        pk: &kem::PublicKey,
+        m: &[u8],
+        rng: &mut R,
+    ) -> Result<CiphertextType> {
+        ccapke_enc(self, pk, m, rng)
+    }
+
+    fn ccapke_dec(&self, sk: &kem::SecretKey, ct: &CiphertextType) -> Result<Vec<u8>> {
+        let (ct, ciphertext, iv) = ct;
+        ccapke_dec(self, sk, ct, ciphertext, iv)
+    }
+
+    fn public_key_to_bytes(&self, pk: &kem::PublicKey) -> Vec<u8> {
+        pk.as_ref().to_vec()
+    }
+
+    fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<kem::PublicKey> {
+        kem::Kem::public_key_from_bytes(self, bytes)
+            .map(|pk| pk.to_owned())
+            .ok_or(AkeError::Decoding("KEM public key"))
+    }
+
+    fn ciphertext_to_bytes(&self, ct: &CiphertextType) -> Vec<u8> {
+        [ct.0.as_ref(), ct.1.as_slice(), ct.2.as_slice()].concat()
+    }
 
-    plaintext
+    fn ciphertext_from_bytes(&self, bytes: &[u8]) -> Result<CiphertextType> {
+        ciphertext_from_bytes(self, bytes)
+    }
 }
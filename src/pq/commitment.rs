@@ -1,9 +1,14 @@
-use crate::common::utils::get_random_key32;
+use rand::{CryptoRng, RngCore};
+
+use crate::common::{
+    suite::Comm,
+    utils::{ct_eq, get_random_bytes},
+};
 use sha3::{Digest, Sha3_256};
 
 // Output: commitment := H(r || x) and open := (x, r)
-pub fn comm(x: &[u8]) -> (Vec<u8>, (Vec<u8>, Vec<u8>)) {
-    let r: Vec<u8> = get_random_key32();
+pub fn comm<R: RngCore + CryptoRng>(x: &[u8], rng: &mut R) -> (Vec<u8>, (Vec<u8>, Vec<u8>)) {
+    let r: Vec<u8> = get_random_bytes(rng, 32);
     let to_commit: Vec<u8> = [r.to_vec(), x.to_vec()].concat();
 
     let mut hasher = Sha3_256::new();
@@ -20,7 +25,17 @@ pub fn comm_vfy(comm: &[u8], open: &(Vec<u8>, Vec<u8>)) -> bool {
     hasher.update(to_commit);
     let commitment: Vec<u8> = hasher.finalize().to_vec();
 
-    let are_equal: bool = commitment.iter().zip(comm.iter()).all(|(a, b)| a == b);
+    ct_eq(&commitment, comm)
+}
+
+pub struct Sha3Commitment;
+
+impl Comm for Sha3Commitment {
+    fn comm<R: RngCore + CryptoRng>(&self, x: &[u8], rng: &mut R) -> (Vec<u8>, (Vec<u8>, Vec<u8>)) {
+        comm(x, rng)
+    }
 
-    are_equal
+    fn comm_vfy(&self, comm: &[u8], open: &(Vec<u8>, Vec<u8>)) -> bool {
+        comm_vfy(comm, open)
+    }
 }
@@ -1,9 +1,23 @@
 use oqs::{kem, sig};
+use sha3::Sha3_256;
+
+use crate::common::{
+    error::Result,
+    prf::PrfAlgorithm,
+    suite::{Prf, Suite, PKE_COINS_LABEL},
+};
+
+use super::{commitment::Sha3Commitment, pke::DEM_NONCE_LENGTH};
+
+pub const SUITE_ID: u8 = 0x01;
 
 pub struct Config {
     users_numbers: u32,
     kem: kem::Kem,
+    // KEM of the CCA-PKE (pk* of round 2), `kem` if not set
+    ccapke_kem: Option<kem::Kem>,
     sig: sig::Sig,
+    prf: PrfAlgorithm,
 }
 
 impl Config {
@@ -11,14 +25,35 @@ impl Config {
         Config {
             users_numbers,
             kem,
+            ccapke_kem: None,
             sig,
+            prf: PrfAlgorithm::AesCtr,
         }
     }
 
+    // Both sides of a handshake must use the same PRF
+    pub fn set_prf_algorithm(&mut self, prf: PrfAlgorithm) {
+        self.prf = prf;
+    }
+
+    pub fn get_prf_algorithm(&self) -> PrfAlgorithm {
+        self.prf
+    }
+
     pub fn get_kem_algorithm(&self) -> &kem::Kem {
         &self.kem
     }
 
+    // The PKE is used once per client in round 2 and the CCA-PKE once per
+    // session, so they may use different KEMs. Both sides must agree on it.
+    pub fn set_ccapke_algorithm(&mut self, kem: kem::Kem) {
+        self.ccapke_kem = Some(kem);
+    }
+
+    pub fn get_ccapke_algorithm(&self) -> &kem::Kem {
+        self.ccapke_kem.as_ref().unwrap_or(&self.kem)
+    }
+
     pub fn get_signature_algorithm(&self) -> &sig::Sig {
         &self.sig
     }
@@ -27,3 +62,47 @@ impl Config {
         self.users_numbers
     }
 }
+
+impl Suite for Config {
+    type Pke = kem::Kem;
+    type CcaPke = kem::Kem;
+    type Sig = sig::Sig;
+    type Comm = Sha3Commitment;
+    type Prf = PrfAlgorithm;
+    type Hash = Sha3_256;
+
+    fn pke(&self) -> &kem::Kem {
+        &self.kem
+    }
+
+    fn ccapke(&self) -> &kem::Kem {
+        self.get_ccapke_algorithm()
+    }
+
+    fn sig(&self) -> &sig::Sig {
+        &self.sig
+    }
+
+    fn comm(&self) -> &Sha3Commitment {
+        &Sha3Commitment
+    }
+
+    fn prf(&self) -> &PrfAlgorithm {
+        &self.prf
+    }
+
+    fn suite_id(&self) -> u8 {
+        SUITE_ID
+    }
+
+    fn get_users_number(&self) -> u32 {
+        self.users_numbers
+    }
+
+    // r_j <- PRF(r, j), as long as r plus the AES-GCM nonce: the KEM gets a
+    // full seed and every recipient its own DEM nonce
+    fn pke_coins(&self, r: &[u8], j: u32) -> Result<Vec<u8>> {
+        self.prf()
+            .prf(r, PKE_COINS_LABEL, j as u64, r.len() + DEM_NONCE_LENGTH)
+    }
+}
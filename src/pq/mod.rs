@@ -4,5 +4,9 @@ pub mod commitment;
 pub mod config;
 pub mod pke;
 pub mod protocol;
+pub mod seeded;
 pub mod server;
+pub mod sig;
+pub mod store;
 pub mod supported_algs;
+pub mod vectors;
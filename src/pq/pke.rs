@@ -4,55 +4,196 @@ use aes_gcm::{
 };
 
 use oqs::kem::{self, Ciphertext};
-// use sha3::{Digest, Sha3_256};
+use rand::{CryptoRng, RngCore};
+use sha3::{Digest, Sha3_256};
+use zeroize::Zeroizing;
 
-use super::protocol::TagType;
+use crate::common::{
+    error::{AkeError, Result},
+    secret::Wipe,
+    suite::Pke,
+    utils::ct_eq,
+    wire::put_field,
+};
+
+use super::{
+    protocol::{CiphertextType, TagType},
+    seeded::{seed_from_rng, with_seed, SEED_LENGTH},
+};
+
+// liboqs-rust gives no mutable access to the bytes of a key, so KEM and
+// signature secret keys are freed without being wiped
+impl Wipe for kem::SecretKey {
+    fn wipe(&mut self) {}
+}
+
+// AES-GCM nonce appended to the KEM seed
+pub const DEM_NONCE_LENGTH: usize = 12;
+pub const DEM_KEY_LENGTH: usize = 32;
+pub const DEM_KEY_LABEL: &[u8] = b"dem key";
 
-fn check_equal(v1: &[u8], v2: &[u8]) -> bool {
-    v1.to_vec()
-        .iter()
-        .zip(v2.to_vec().iter())
-        .all(|(a, b)| a == b)
+// AES-256-GCM cipher keyed by a KEM shared secret. A 32-byte secret is the key
+// itself. Other lengths (16 and 24 bytes for FrodoKEM-640/976, 64 for HQC)
+// give the key SHA3-256(enc("dem key") || enc(k)).
+pub fn dem_cipher(k: &[u8]) -> Result<Aes256Gcm> {
+    let key = if k.len() == DEM_KEY_LENGTH {
+        Zeroizing::new(k.to_vec())
+    } else {
+        let mut input = Zeroizing::new(Vec::new());
+        put_field(&mut input, DEM_KEY_LABEL);
+        put_field(&mut input, k);
+        let mut hasher = Sha3_256::new();
+        hasher.update(input.as_slice());
+        Zeroizing::new(hasher.finalize().to_vec())
+    };
+
+    Aes256Gcm::new_from_slice(&key).map_err(|_| AkeError::Primitive("AES-GCM key setup"))
 }
 
 pub fn check_ciphertext(
     c1: &(Ciphertext, Vec<u8>, TagType),
     c2: &(Ciphertext, Vec<u8>, TagType),
 ) -> bool {
-    let are_equal0: bool = check_equal(&c1.0.clone().into_vec(), &c2.0.clone().into_vec());
-    let are_equal1: bool = check_equal(&c1.1, &c2.1);
-    let are_equal2: bool = check_equal(&c1.2, &c2.2);
+    // Every part is compared, whether or not an earlier one differs
+    let are_equal0: bool = ct_eq(c1.0.as_ref(), c2.0.as_ref());
+    let are_equal1: bool = ct_eq(&c1.1, &c2.1);
+    let are_equal2: bool = ct_eq(&c1.2, &c2.2);
+
+    are_equal0 & are_equal1 & are_equal2
+}
+
+// Inverse of `ciphertext_to_bytes`: ct_kem || ct_dem || iv
+pub fn ciphertext_from_bytes(kem: &kem::Kem, bytes: &[u8]) -> Result<CiphertextType> {
+    let kem_len = kem.length_ciphertext();
+    if bytes.len() < kem_len + 12 {
+        return Err(AkeError::Decoding("KEM/DEM ciphertext"));
+    }
+    let (ct_kem, rest) = bytes.split_at(kem_len);
+    let (ct_dem, iv) = rest.split_at(rest.len() - 12);
+    let ct_kem = kem::Kem::ciphertext_from_bytes(kem, ct_kem)
+        .ok_or(AkeError::Decoding("KEM ciphertext"))?
+        .to_owned();
+
+    Ok((ct_kem, ct_dem.to_vec(), *TagType::from_slice(iv)))
+}
+
+// Encapsulation with the randomness of liboqs drawn from `seed`, so that the
+// same seed and public key always give the same ciphertext and secret
+pub fn encapsulate(
+    kem: &kem::Kem,
+    pk: &kem::PublicKey,
+    seed: &[u8],
+) -> Result<(Ciphertext, kem::SharedSecret)> {
+    let seed = seed
+        .try_into()
+        .map_err(|_| AkeError::Decoding("KEM seed"))?;
+    with_seed(seed, || kem.encapsulate(pk)).map_err(|_| AkeError::Primitive("KEM encapsulation"))
+}
 
-    are_equal0 && are_equal1 && are_equal2
+// Key generation with the randomness of liboqs drawn from `rng`
+pub fn keypair<R: RngCore + CryptoRng>(
+    kem: &kem::Kem,
+    rng: &mut R,
+) -> Result<(kem::PublicKey, kem::SecretKey)> {
+    with_seed(&seed_from_rng(rng), || kem.keypair())
+        .map_err(|_| AkeError::Primitive("KEM key generation"))
 }
 
 pub fn pke_enc(
     kem: &kem::Kem,
     pk: &kem::PublicKey,
-    m: &Vec<u8>,
-    r: &Vec<u8>,
-) -> (Ciphertext, Vec<u8>, TagType) {
-    // let mut hasher = Sha3_256::new();
-    // hasher.update(r);
-    // let nonce = hasher.finalize().to_vec();
-    let (ct, k) = kem.encapsulate(pk, r).unwrap();
-    let cipher = Aes256Gcm::new_from_slice(k.into_vec().as_slice()).unwrap();
-    let iv = Nonce::from_slice(&r[0..12]);
-    let ciphertext = cipher.encrypt(iv, m.as_slice()).unwrap();
+    m: &[u8],
+    r: &[u8],
+) -> Result<(Ciphertext, Vec<u8>, TagType)> {
+    // r := KEM seed || DEM nonce, see `Config::pke_coins`
+    if r.len() != SEED_LENGTH + DEM_NONCE_LENGTH {
+        return Err(AkeError::Decoding("PKE coins"));
+    }
+    let (seed, nonce) = r.split_at(SEED_LENGTH);
+    let (ct, k) = encapsulate(kem, pk, seed)?;
+    let cipher = dem_cipher(k.as_ref())?;
+    let iv = Nonce::from_slice(nonce);
+    let ciphertext = cipher
+        .encrypt(iv, m)
+        .map_err(|_| AkeError::Primitive("AES-GCM encryption"))?;
 
-    (ct, ciphertext, *iv)
+    Ok((ct, ciphertext, *iv))
 }
 
 pub fn pke_dec(
     kem: &kem::Kem,
-    sk: kem::SecretKey,
+    sk: &kem::SecretKey,
     ct: &Ciphertext,
-    ciphertext: &Vec<u8>,
+    ciphertext: &[u8],
     iv: &TagType,
-) -> Vec<u8> {
-    let k = kem.decapsulate(&sk, ct).unwrap();
-    let cipher = Aes256Gcm::new_from_slice(k.into_vec().as_slice()).unwrap();
-    let plaintext = cipher.decrypt(iv, ciphertext.as_ref()).unwrap();
+) -> Result<Vec<u8>> {
+    let k = kem
+        .decapsulate(sk, ct)
+        .map_err(|_| AkeError::Primitive("KEM decapsulation"))?;
+    let cipher = dem_cipher(k.as_ref())?;
+    cipher
+        .decrypt(iv, ciphertext)
+        .map_err(|_| AkeError::Verification("PKE ciphertext"))
+}
+
+impl Pke for kem::Kem {
+    type PublicKey = kem::PublicKey;
+    type SecretKey = kem::SecretKey;
+    type Ciphertext = CiphertextType;
+
+    fn algorithm_name(&self) -> String {
+        self.algorithm().name().to_string()
+    }
+
+    fn pke_gen<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(kem::PublicKey, kem::SecretKey)> {
+        keypair(self, rng)
+    }
+
+    fn pke_enc(&self, pk: &kem::PublicKey, m: &[u8], r: &[u8]) -> Result<CiphertextType> {
+        pke_enc(self, pk, m, r)
+    }
+
+    fn pke_dec(&self, sk: &kem::SecretKey, ct: &CiphertextType) -> Result<Vec<u8>> {
+        let (ct, ciphertext, iv) = ct;
+        pke_dec(self, sk, ct, ciphertext, iv)
+    }
+
+    fn check_ciphertext(&self, c1: &CiphertextType, c2: &CiphertextType) -> bool {
+        check_ciphertext(c1, c2)
+    }
+
+    fn pke_randomness<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Result<Vec<u8>> {
+        Ok(seed_from_rng(rng).to_vec())
+    }
+
+    fn public_key_to_bytes(&self, pk: &kem::PublicKey) -> Vec<u8> {
+        pk.as_ref().to_vec()
+    }
+
+    fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<kem::PublicKey> {
+        kem::Kem::public_key_from_bytes(self, bytes)
+            .map(|pk| pk.to_owned())
+            .ok_or(AkeError::Decoding("KEM public key"))
+    }
+
+    fn secret_key_to_bytes(&self, sk: &kem::SecretKey) -> Vec<u8> {
+        sk.as_ref().to_vec()
+    }
+
+    fn secret_key_from_bytes(&self, bytes: &[u8]) -> Result<kem::SecretKey> {
+        kem::Kem::secret_key_from_bytes(self, bytes)
+            .map(|sk| sk.to_owned())
+            .ok_or(AkeError::Decoding("KEM secret key"))
+    }
+
+    fn ciphertext_to_bytes(&self, ct: &CiphertextType) -> Vec<u8> {
+        [ct.0.as_ref(), ct.1.as_slice(), ct.2.as_slice()].concat()
+    }
 
-    plaintext
+    fn ciphertext_from_bytes(&self, bytes: &[u8]) -> Result<CiphertextType> {
+        ciphertext_from_bytes(self, bytes)
+    }
 }
@@ -0,0 +1,57 @@
+// liboqs draws every random byte through OQS_randombytes. The crate installs
+// its own source there: inside `with_seed` the bytes come from a ChaCha20
+// stream keyed by the seed, so the liboqs call is a deterministic function of
+// it, and elsewhere from the OS. The stream is per thread, as liboqs samples
+// on the calling thread.
+
+use std::{cell::RefCell, slice, sync::Once};
+
+use oqs::ffi::rand::OQS_randombytes_custom_algorithm;
+use rand::{rngs::OsRng, CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+// Length of the seed replacing the randomness of one liboqs call
+pub const SEED_LENGTH: usize = 32;
+
+thread_local! {
+    static STREAM: RefCell<Option<ChaCha20Rng>> = const { RefCell::new(None) };
+}
+
+static INSTALL: Once = Once::new();
+
+unsafe extern "C" fn randombytes(out: *mut u8, len: usize) {
+    if len == 0 {
+        return;
+    }
+    // SAFETY: liboqs passes a buffer of `len` writable bytes
+    let out = unsafe { slice::from_raw_parts_mut(out, len) };
+    STREAM.with(|stream| match stream.borrow_mut().as_mut() {
+        Some(rng) => rng.fill_bytes(out),
+        None => OsRng.fill_bytes(out),
+    });
+}
+
+// Puts the previous stream back even if `f` panics
+struct Restore(Option<ChaCha20Rng>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        STREAM.with(|stream| *stream.borrow_mut() = previous);
+    }
+}
+
+// Runs `f` with liboqs randomness drawn from `seed`
+pub fn with_seed<T>(seed: &[u8; SEED_LENGTH], f: impl FnOnce() -> T) -> T {
+    INSTALL.call_once(|| unsafe { OQS_randombytes_custom_algorithm(Some(randombytes)) });
+
+    let previous = STREAM.with(|stream| stream.replace(Some(ChaCha20Rng::from_seed(*seed))));
+    let _restore = Restore(previous);
+    f()
+}
+
+pub fn seed_from_rng<R: RngCore + CryptoRng>(rng: &mut R) -> [u8; SEED_LENGTH] {
+    let mut seed = [0u8; SEED_LENGTH];
+    rng.fill_bytes(&mut seed);
+    seed
+}
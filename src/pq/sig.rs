@@ -0,0 +1,90 @@
+use oqs::sig;
+use rand::{CryptoRng, RngCore};
+use sha3::{Digest, Sha3_256};
+use zeroize::Zeroizing;
+
+use crate::common::{
+    error::{AkeError, Result},
+    secret::Wipe,
+    suite::Sig,
+    wire::put_field,
+};
+
+use super::seeded::{seed_from_rng, with_seed, SEED_LENGTH};
+
+pub const SIGNING_SEED_LABEL: &[u8] = b"signing seed";
+
+// See `Wipe for kem::SecretKey`
+impl Wipe for sig::SecretKey {
+    fn wipe(&mut self) {}
+}
+
+// liboqs randomises signing (hedged Dilithium and ML-DSA, Falcon, SPHINCS+).
+// Its randomness is drawn from H(enc(label) || enc(sk) || enc(m)) instead, so
+// that a signature is a function of the key and message, as ECDSA with RFC
+// 6979 is in the classic suite.
+fn signing_seed(sk: &sig::SecretKey, m: &[u8]) -> [u8; SEED_LENGTH] {
+    let mut input = Zeroizing::new(Vec::new());
+    for field in [SIGNING_SEED_LABEL, sk.as_ref(), m] {
+        put_field(&mut input, field);
+    }
+
+    Sha3_256::digest(input.as_slice()).into()
+}
+
+impl Sig for sig::Sig {
+    type PublicKey = sig::PublicKey;
+    type SecretKey = sig::SecretKey;
+    type Signature = sig::Signature;
+
+    fn algorithm_name(&self) -> String {
+        self.algorithm().name().to_string()
+    }
+
+    fn sig_gen<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(sig::PublicKey, sig::SecretKey)> {
+        with_seed(&seed_from_rng(rng), || self.keypair())
+            .map_err(|_| AkeError::Primitive("signature key generation"))
+    }
+
+    fn sig_sign(&self, sk: &sig::SecretKey, m: &[u8]) -> Result<sig::Signature> {
+        with_seed(&signing_seed(sk, m), || self.sign(m, sk))
+            .map_err(|_| AkeError::Primitive("signing"))
+    }
+
+    fn sig_vry(&self, pk: &sig::PublicKey, m: &[u8], signature: &sig::Signature) -> bool {
+        self.verify(m, signature, pk).is_ok()
+    }
+
+    fn public_key_to_bytes(&self, pk: &sig::PublicKey) -> Vec<u8> {
+        pk.as_ref().to_vec()
+    }
+
+    fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<sig::PublicKey> {
+        sig::Sig::public_key_from_bytes(self, bytes)
+            .map(|pk| pk.to_owned())
+            .ok_or(AkeError::Decoding("signature public key"))
+    }
+
+    fn secret_key_to_bytes(&self, sk: &sig::SecretKey) -> Vec<u8> {
+        sk.as_ref().to_vec()
+    }
+
+    fn secret_key_from_bytes(&self, bytes: &[u8]) -> Result<sig::SecretKey> {
+        sig::Sig::secret_key_from_bytes(self, bytes)
+            .map(|sk| sk.to_owned())
+            .ok_or(AkeError::Decoding("signature secret key"))
+    }
+
+    fn signature_to_bytes(&self, signature: &sig::Signature) -> Vec<u8> {
+        signature.as_ref().to_vec()
+    }
+
+    fn signature_from_bytes(&self, bytes: &[u8]) -> Result<sig::Signature> {
+        sig::Sig::signature_from_bytes(self, bytes)
+            .map(|signature| signature.to_owned())
+            .ok_or(AkeError::Decoding("signature"))
+    }
+}
@@ -1,15 +1,48 @@
 use oqs::{kem, sig};
 
+use crate::common::error::{AkeError, Result};
+
+// FIPS 203 and FIPS 204 names come first. The round-3 Kyber and Dilithium
+// names still select the round-3 schemes, which do not interoperate with
+// ML-KEM and ML-DSA.
 pub static SUPPORTED_KEMS: &[&str] = &[
+    "ML-KEM-512",
+    "ML-KEM-768",
+    "ML-KEM-1024",
     "Kyber512",
     "Kyber768",
     "Kyber1024",
     "ClassicMcEliece348864f",
     "ClassicMcEliece460896f",
     "ClassicMcEliece6960119f",
+    "BikeL1",
+    "BikeL3",
+    "BikeL5",
+    "Hqc128",
+    "Hqc192",
+    "Hqc256",
+    "FrodoKem640Aes",
+    "FrodoKem640Shake",
+    "FrodoKem976Aes",
+    "FrodoKem976Shake",
+    "FrodoKem1344Aes",
+    "FrodoKem1344Shake",
 ];
 
-pub static SUPPORTED_SIGS: &[&str] = &["Dilithium2", "Dilithium3", "Dilithium5"];
+pub static SUPPORTED_SIGS: &[&str] = &[
+    "ML-DSA-44",
+    "ML-DSA-65",
+    "ML-DSA-87",
+    "Dilithium2",
+    "Dilithium3",
+    "Dilithium5",
+    "Falcon512",
+    "Falcon1024",
+    "SphincsSha2128fSimple",
+    "SphincsSha2128sSimple",
+    "SphincsShake128fSimple",
+    "SphincsShake128sSimple",
+];
 
 fn print_static_array(arr: &'static [&str]) {
     for elem in arr {
@@ -21,33 +54,57 @@ pub fn print_supported_kems() {
     print_static_array(SUPPORTED_KEMS);
 }
 
-pub fn get_kem_algorithm(kem: &str) -> Option<kem::Kem> {
-    match kem {
-        "Kyber512" => Some(kem::Kem::new(kem::Algorithm::Kyber512).unwrap()),
-        "Kyber768" => Some(kem::Kem::new(kem::Algorithm::Kyber768).unwrap()),
-        "Kyber1024" => Some(kem::Kem::new(kem::Algorithm::Kyber1024).unwrap()),
-        "ClassicMcEliece348864f" => {
-            Some(kem::Kem::new(kem::Algorithm::ClassicMcEliece348864f).unwrap())
-        }
-        "ClassicMcEliece460896f" => {
-            Some(kem::Kem::new(kem::Algorithm::ClassicMcEliece460896f).unwrap())
-        }
-        "ClassicMcEliece6960119f" => {
-            Some(kem::Kem::new(kem::Algorithm::ClassicMcEliece6960119f).unwrap())
-        }
-        _ => None,
-    }
+// Unknown names are a decoding error, a scheme liboqs was built without a
+// primitive one
+pub fn get_kem_algorithm(kem: &str) -> Result<kem::Kem> {
+    let algorithm = match kem {
+        "ML-KEM-512" => kem::Algorithm::MlKem512,
+        "ML-KEM-768" => kem::Algorithm::MlKem768,
+        "ML-KEM-1024" => kem::Algorithm::MlKem1024,
+        "Kyber512" => kem::Algorithm::Kyber512,
+        "Kyber768" => kem::Algorithm::Kyber768,
+        "Kyber1024" => kem::Algorithm::Kyber1024,
+        "ClassicMcEliece348864f" => kem::Algorithm::ClassicMcEliece348864f,
+        "ClassicMcEliece460896f" => kem::Algorithm::ClassicMcEliece460896f,
+        "ClassicMcEliece6960119f" => kem::Algorithm::ClassicMcEliece6960119f,
+        "BikeL1" => kem::Algorithm::BikeL1,
+        "BikeL3" => kem::Algorithm::BikeL3,
+        "BikeL5" => kem::Algorithm::BikeL5,
+        "Hqc128" => kem::Algorithm::Hqc128,
+        "Hqc192" => kem::Algorithm::Hqc192,
+        "Hqc256" => kem::Algorithm::Hqc256,
+        "FrodoKem640Aes" => kem::Algorithm::FrodoKem640Aes,
+        "FrodoKem640Shake" => kem::Algorithm::FrodoKem640Shake,
+        "FrodoKem976Aes" => kem::Algorithm::FrodoKem976Aes,
+        "FrodoKem976Shake" => kem::Algorithm::FrodoKem976Shake,
+        "FrodoKem1344Aes" => kem::Algorithm::FrodoKem1344Aes,
+        "FrodoKem1344Shake" => kem::Algorithm::FrodoKem1344Shake,
+        _ => return Err(AkeError::Decoding("KEM name")),
+    };
+
+    kem::Kem::new(algorithm).map_err(|_| AkeError::Primitive("KEM initialisation"))
 }
 
 pub fn print_supported_signatures() {
     print_static_array(SUPPORTED_SIGS);
 }
 
-pub fn get_signature_algorithm(sig: &str) -> Option<sig::Sig> {
-    match sig {
-        "Dilithium2" => Some(sig::Sig::new(sig::Algorithm::Dilithium2).unwrap()),
-        "Dilithium3" => Some(sig::Sig::new(sig::Algorithm::Dilithium3).unwrap()),
-        "Dilithium5" => Some(sig::Sig::new(sig::Algorithm::Dilithium5).unwrap()),
-        _ => None,
-    }
+pub fn get_signature_algorithm(sig: &str) -> Result<sig::Sig> {
+    let algorithm = match sig {
+        "ML-DSA-44" => sig::Algorithm::MlDsa44,
+        "ML-DSA-65" => sig::Algorithm::MlDsa65,
+        "ML-DSA-87" => sig::Algorithm::MlDsa87,
+        "Dilithium2" => sig::Algorithm::Dilithium2,
+        "Dilithium3" => sig::Algorithm::Dilithium3,
+        "Dilithium5" => sig::Algorithm::Dilithium5,
+        "Falcon512" => sig::Algorithm::Falcon512,
+        "Falcon1024" => sig::Algorithm::Falcon1024,
+        "SphincsSha2128fSimple" => sig::Algorithm::SphincsSha2128fSimple,
+        "SphincsSha2128sSimple" => sig::Algorithm::SphincsSha2128sSimple,
+        "SphincsShake128fSimple" => sig::Algorithm::SphincsShake128fSimple,
+        "SphincsShake128sSimple" => sig::Algorithm::SphincsShake128sSimple,
+        _ => return Err(AkeError::Decoding("signature name")),
+    };
+
+    sig::Sig::new(algorithm).map_err(|_| AkeError::Primitive("signature initialisation"))
 }
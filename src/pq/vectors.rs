@@ -0,0 +1,71 @@
+// Known-answer vectors of the PQ suite, see `common::vectors`. The suite name
+// of a vector, e.g. "Kyber768+Dilithium3", gives its KEMs and signature
+// scheme by their liboqs names.
+
+use oqs::{kem, sig};
+
+use super::{
+    config::Config,
+    supported_algs::{get_kem_algorithm, get_signature_algorithm, SUPPORTED_KEMS, SUPPORTED_SIGS},
+};
+use crate::common::{
+    error::{AkeError, Result},
+    vectors::{check_vector, check_vectors, generate_vectors, vector_parameters, TestVector},
+};
+
+pub use crate::common::vectors::{read_vectors, write_vectors};
+
+// Shipped with the crate and checked by `cargo test`
+pub const VECTORS_FILE: &str = "vectors/pq.json";
+
+// Supported KEM whose liboqs name is `name`
+pub fn kem_named(name: &str) -> Result<kem::Kem> {
+    SUPPORTED_KEMS
+        .iter()
+        .filter_map(|alg| get_kem_algorithm(alg).ok())
+        .find(|kem| kem.algorithm().name() == name)
+        .ok_or(AkeError::Decoding("test vector: KEM"))
+}
+
+// Splits "<rest>+<signature scheme>". SPHINCS+ names hold a '+' themselves,
+// so the scheme is matched against the supported ones rather than split off.
+pub fn split_signature(suite: &str) -> Result<(&str, sig::Sig)> {
+    SUPPORTED_SIGS
+        .iter()
+        .filter_map(|alg| get_signature_algorithm(alg).ok())
+        .find_map(|sig| {
+            let rest = suite
+                .strip_suffix(sig.algorithm().name())?
+                .strip_suffix('+')?;
+            Some((rest, sig))
+        })
+        .ok_or(AkeError::Decoding("test vector: signature scheme"))
+}
+
+// Configuration a vector was generated with
+pub fn vector_config(vector: &TestVector) -> Result<Config> {
+    let (users, prf) = vector_parameters(vector)?;
+    let (kems, sig) = split_signature(&vector.suite)?;
+    let (pke, ccapke) = kems.split_once('/').unwrap_or((kems, kems));
+
+    let mut config = Config::new(users, kem_named(pke)?, sig);
+    if ccapke != pke {
+        config.set_ccapke_algorithm(kem_named(ccapke)?);
+    }
+    config.set_prf_algorithm(prf);
+
+    Ok(config)
+}
+
+pub fn check(vector: &TestVector) -> Result<()> {
+    check_vector(&vector_config(vector)?, vector)
+}
+
+pub fn generate(config: &Config, number: u64) -> Result<Vec<TestVector>> {
+    generate_vectors(config, number)
+}
+
+// Checks every vector and prints one line per seed. False if any fails.
+pub fn check_all(vectors: &[TestVector]) -> bool {
+    check_vectors(vectors, check)
+}
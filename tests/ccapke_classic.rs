@@ -2,16 +2,17 @@ use generic_anon_ake::{
     classic::ccapke::{ccapke_dec, ccapke_enc, ccapke_gen},
     common::utils::get_random_key32,
 };
+use rand::rngs::OsRng;
 
 #[test]
 fn ccapke_classic_works() {
-    let (pk, sk) = ccapke_gen();
+    let (pk, sk) = ccapke_gen(&mut OsRng);
 
     let m = get_random_key32();
-    let ct = ccapke_enc(&pk, &m);
-    let ct2 = ccapke_enc(&pk, &m);
+    let ct = ccapke_enc(&pk, &m, &mut OsRng).unwrap();
+    let ct2 = ccapke_enc(&pk, &m, &mut OsRng).unwrap();
 
-    let m2 = ccapke_dec(&sk, &ct);
+    let m2 = ccapke_dec(&sk, &ct).unwrap();
 
     assert_eq!(m, m2);
     assert_ne!(ct, ct2);
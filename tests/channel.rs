@@ -0,0 +1,243 @@
+mod common;
+
+use std::{
+    collections::VecDeque,
+    io::{self, Cursor, Read, Write},
+    net::{TcpListener, TcpStream},
+    thread,
+};
+
+use generic_anon_ake::{
+    classic,
+    common::{
+        channel::SecureChannel,
+        error::AkeError,
+        net::{connect_session, recv_frame, send_frame, serve_session},
+        protocol::registration,
+    },
+};
+use rand::rngs::OsRng;
+
+use common::handshake;
+
+// In-memory transport: reads from `input` and records what is written
+#[derive(Default)]
+struct Tape {
+    input: VecDeque<u8>,
+    output: Vec<u8>,
+    // Makes every write fail, as a dropped connection would
+    broken: bool,
+}
+
+impl Read for Tape {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.input.read(buf)
+    }
+}
+
+impl Write for Tape {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.broken {
+            return Err(io::ErrorKind::BrokenPipe.into());
+        }
+        self.output.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Splits what a channel wrote into its records
+fn records(tape: &Tape) -> Vec<Vec<u8>> {
+    let mut cursor = Cursor::new(&tape.output);
+    let mut records = Vec::new();
+    while (cursor.position() as usize) < tape.output.len() {
+        records.push(recv_frame(&mut cursor).unwrap());
+    }
+    records
+}
+
+fn deliver(channel: &mut SecureChannel<Tape>, record: &[u8]) {
+    send_frame(&mut channel.get_mut().input, record).unwrap();
+}
+
+// Client channel with three data records written, and the matching server channel
+fn channels() -> (SecureChannel<Tape>, SecureChannel<Tape>, Vec<Vec<u8>>) {
+    let config = classic::config::Config::new(2);
+    let (client, server, handle) = handshake(&config);
+    let mut client = SecureChannel::client(&client, Tape::default()).unwrap();
+    let server = SecureChannel::server(&server, handle, Tape::default()).unwrap();
+
+    for data in [b"first".as_slice(), b"second", b"third"] {
+        client.send(data).unwrap();
+    }
+    let records = records(client.get_ref());
+
+    (client, server, records)
+}
+
+#[test]
+fn records_are_delivered_in_order() {
+    let (_, mut server, records) = channels();
+
+    for record in records.iter() {
+        deliver(&mut server, record);
+    }
+
+    assert_eq!(server.recv().unwrap().unwrap(), b"first");
+    assert_eq!(server.recv().unwrap().unwrap(), b"second");
+    assert_eq!(server.recv().unwrap().unwrap(), b"third");
+}
+
+#[test]
+fn records_are_encrypted() {
+    let (_, _, records) = channels();
+
+    assert!(!records[0].windows(5).any(|window| window == b"first"));
+}
+
+#[test]
+fn replayed_and_reordered_records_are_rejected() {
+    let (_, mut server, records) = channels();
+
+    deliver(&mut server, &records[0]);
+    deliver(&mut server, &records[0]);
+    assert_eq!(server.recv().unwrap().unwrap(), b"first");
+    assert_eq!(
+        server.recv(),
+        Err(AkeError::UnexpectedRecord {
+            expected: 1,
+            received: 0
+        })
+    );
+
+    deliver(&mut server, &records[2]);
+    assert_eq!(
+        server.recv(),
+        Err(AkeError::UnexpectedRecord {
+            expected: 1,
+            received: 2
+        })
+    );
+}
+
+#[test]
+fn tampered_records_are_rejected() {
+    let (_, mut server, records) = channels();
+
+    let mut record = records[0].clone();
+    let last = record.len() - 1;
+    record[last] ^= 0x01;
+    deliver(&mut server, &record);
+    assert_eq!(server.recv(), Err(AkeError::Verification("record")));
+
+    // The sequence number and type are authenticated too
+    let mut record = records[1].clone();
+    record[8] = 0;
+    deliver(&mut server, &record);
+    assert_eq!(server.recv(), Err(AkeError::Verification("record")));
+}
+
+#[test]
+fn records_do_not_reflect_to_their_sender() {
+    let (mut client, _, records) = channels();
+
+    deliver(&mut client, &records[0]);
+    assert_eq!(client.recv(), Err(AkeError::Verification("record")));
+}
+
+#[test]
+fn close_ends_each_direction() {
+    let (mut client, mut server, _) = channels();
+
+    client.close().unwrap();
+    assert_eq!(client.send(b"late"), Err(AkeError::ChannelClosed));
+    for record in records(client.get_ref()).iter() {
+        deliver(&mut server, record);
+    }
+    for _ in 0..3 {
+        assert!(server.recv().unwrap().is_some());
+    }
+    assert_eq!(server.recv(), Ok(None));
+    assert_eq!(server.recv(), Ok(None));
+    assert!(!server.is_closed());
+
+    // The server can still answer before closing its own direction
+    server.send(b"bye").unwrap();
+    server.close().unwrap();
+    assert!(server.is_closed());
+    for record in records(server.get_ref()).iter() {
+        deliver(&mut client, record);
+    }
+    assert_eq!(client.recv().unwrap().unwrap(), b"bye");
+    assert_eq!(client.recv(), Ok(None));
+    assert!(client.is_closed());
+}
+
+#[test]
+fn a_failed_send_breaks_the_channel() {
+    let (mut client, mut server, sent) = channels();
+
+    client.get_mut().broken = true;
+    assert_eq!(
+        client.send(b"lost"),
+        Err(AkeError::Io(io::ErrorKind::BrokenPipe))
+    );
+    assert!(client.is_broken());
+
+    // No record is numbered after one the peer may never have seen
+    client.get_mut().broken = false;
+    assert_eq!(client.send(b"fourth"), Err(AkeError::ChannelBroken));
+    assert_eq!(records(client.get_ref()).len(), sent.len());
+
+    for record in sent.iter() {
+        deliver(&mut server, record);
+    }
+    for _ in 0..sent.len() {
+        assert!(server.recv().unwrap().is_some());
+    }
+}
+
+#[test]
+fn channel_needs_a_completed_session() {
+    let config = classic::config::Config::new(2);
+    let (_, clients) = registration(&config, &mut OsRng).unwrap();
+
+    assert!(matches!(
+        SecureChannel::client(&clients[0], Tape::default()),
+        Err(AkeError::MissingState("key schedule"))
+    ));
+}
+
+#[test]
+fn channel_over_tcp_after_the_handshake() {
+    let config = classic::config::Config::new(2);
+    let (mut server, mut clients) = registration(&config, &mut OsRng).unwrap();
+    let mut client = clients.remove(1);
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let server_thread = thread::spawn(move || {
+        let config = classic::config::Config::new(2);
+        let (mut stream, _) = listener.accept().unwrap();
+        let handle =
+            serve_session(&mut server, &config, &mut stream, &mut OsRng, false, false).unwrap();
+
+        let mut channel = SecureChannel::server(&server, handle, stream).unwrap();
+        while let Some(data) = channel.recv().unwrap() {
+            channel.send(&data.to_ascii_uppercase()).unwrap();
+        }
+        channel.close().unwrap();
+    });
+
+    let mut stream = TcpStream::connect(address).unwrap();
+    connect_session(&mut client, &config, &mut stream, &mut OsRng, false, false).unwrap();
+    let mut channel = SecureChannel::client(&client, stream).unwrap();
+
+    channel.send(b"hello").unwrap();
+    assert_eq!(channel.recv().unwrap().unwrap(), b"HELLO");
+    channel.close().unwrap();
+    assert_eq!(channel.recv(), Ok(None));
+    server_thread.join().unwrap();
+}
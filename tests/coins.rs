@@ -0,0 +1,80 @@
+use std::collections::HashSet;
+
+use generic_anon_ake::{
+    common::{
+        prf::prf_with_length,
+        protocol::{registration, round_1, round_2},
+        suite::{Pke, Suite},
+        utils::get_random_key32,
+    },
+    pq::{
+        self,
+        pke::DEM_NONCE_LENGTH,
+        supported_algs::{get_kem_algorithm, get_signature_algorithm},
+    },
+};
+use rand::rngs::OsRng;
+
+fn pq_config(users: u32) -> pq::config::Config {
+    let kemalg = get_kem_algorithm("Kyber768").unwrap();
+    let sigalg = get_signature_algorithm("Dilithium2").unwrap();
+    pq::config::Config::new(users, kemalg, sigalg)
+}
+
+#[test]
+fn pq_coins_differ_per_recipient() {
+    let config = pq_config(8);
+    let r = config.pke().pke_randomness(&mut OsRng).unwrap();
+
+    let coins: Vec<Vec<u8>> = (0..8).map(|j| config.pke_coins(&r, j).unwrap()).collect();
+
+    for rj in coins.iter() {
+        assert_eq!(rj.len(), r.len() + DEM_NONCE_LENGTH);
+        assert_ne!(&rj[..r.len()], &r[..]);
+    }
+    let distinct: HashSet<&Vec<u8>> = coins.iter().collect();
+    assert_eq!(distinct.len(), coins.len());
+    let nonces: HashSet<&[u8]> = coins
+        .iter()
+        .map(|rj| &rj[rj.len() - DEM_NONCE_LENGTH..])
+        .collect();
+    assert_eq!(nonces.len(), coins.len());
+
+    // Same r and j, same coins: clients recompute them in round 5
+    assert_eq!(config.pke_coins(&r, 3).unwrap(), coins[3]);
+}
+
+#[test]
+fn pq_round_2_uses_distinct_coins_per_recipient() {
+    let config = pq_config(6);
+    let (mut server, mut clients) = registration(&config, &mut OsRng).unwrap();
+    let m1 = round_1(&mut clients[0], &config, &mut OsRng).unwrap();
+    let handle = clients[0].send_m1(m1, &mut server);
+
+    let ((cis, _, _), _, _) = round_2(&mut server, &config, handle, &mut OsRng).unwrap();
+
+    let ivs: HashSet<Vec<u8>> = cis.iter().map(|(_, _, iv)| iv.to_vec()).collect();
+    assert_eq!(ivs.len(), cis.len());
+    let kem_cts: HashSet<Vec<u8>> = cis.iter().map(|(ct, _, _)| ct.clone().into_vec()).collect();
+    assert_eq!(kem_cts.len(), cis.len());
+}
+
+#[test]
+fn prf_keystreams_of_consecutive_indices_do_not_overlap() {
+    let key = get_random_key32();
+    let mut nonce0 = [0u8; 16];
+    nonce0[..8].copy_from_slice(&0u64.to_be_bytes());
+    let mut nonce1 = [0u8; 16];
+    nonce1[..8].copy_from_slice(&1u64.to_be_bytes());
+
+    let out0 = prf_with_length(&key, &nonce0, 64).unwrap();
+    let out1 = prf_with_length(&key, &nonce1, 64).unwrap();
+
+    for block in out0.chunks(16) {
+        assert!(!out1.chunks(16).any(|other| other == block));
+    }
+    assert_eq!(
+        &prf_with_length(&key, &nonce0, 16).unwrap()[..],
+        &out0[..16]
+    );
+}
@@ -1,14 +1,34 @@
-use generic_anon_ake::pq::commitment::{comm, comm_vfy};
+use generic_anon_ake::{
+    classic,
+    pq::commitment::{comm, comm_vfy},
+};
+use rand::rngs::OsRng;
 
 #[test]
 fn commitment_works() {
     let x: Vec<u8> = vec![1, 2, 3, 4, 5, 6];
-    let (commitment, open) = comm(&x);
+    let (commitment, open) = comm(&x, &mut OsRng);
     let (x, r) = open.clone();
     assert_eq!(commitment.len(), 32);
     assert_eq!(r.len(), 32);
     assert_eq!(x.len(), 6);
 
     let are_equal = comm_vfy(&commitment, &open);
-    assert_eq!(are_equal, true);
+    assert!(are_equal);
+}
+
+#[test]
+fn truncated_and_empty_commitments_are_rejected() {
+    let x: Vec<u8> = vec![1, 2, 3, 4, 5, 6];
+
+    let (commitment, open) = comm(&x, &mut OsRng);
+    assert!(!comm_vfy(&commitment[..31], &open));
+    assert!(!comm_vfy(&commitment[..1], &open));
+    assert!(!comm_vfy(&[], &open));
+    assert!(!comm_vfy(&[commitment.clone(), vec![0]].concat(), &open));
+
+    let (commitment, open) = classic::commitment::comm(&x, &mut OsRng);
+    assert!(classic::commitment::comm_vfy(&commitment, &open));
+    assert!(!classic::commitment::comm_vfy(&commitment[..31], &open));
+    assert!(!classic::commitment::comm_vfy(&[], &open));
 }
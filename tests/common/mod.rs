@@ -0,0 +1,83 @@
+// Handshake fixtures shared by the integration tests. Every test binary
+// compiles its own copy and only uses part of it.
+#![allow(dead_code)]
+
+use generic_anon_ake::common::{
+    client::Client,
+    error::{AkeError, Result},
+    protocol::{registration, round_1, round_2, round_3, round_4, round_5, round_6, SessionHandle},
+    server::Server,
+    suite::Suite,
+    wire::{
+        decode_m1, decode_m2, decode_m3, decode_m4, decode_m5, encode_m1, encode_m2, encode_m3,
+        encode_m4, encode_m5,
+    },
+};
+use rand::{rngs::OsRng, CryptoRng, RngCore};
+
+// Registration, then rounds 1 to `last` between the server and its first
+// client. Each of m1, ..., m5 travels in its wire encoding and `relay` sees
+// the bytes of message i before they are decoded, so it may record or alter
+// them. A message that names another session than its own is refused.
+pub fn run_rounds_with<S, R, F>(
+    config: &S,
+    rng: &mut R,
+    last: usize,
+    mut relay: F,
+) -> Result<(Client<S>, Server<S>, SessionHandle)>
+where
+    S: Suite,
+    R: RngCore + CryptoRng,
+    F: FnMut(usize, Vec<u8>) -> Vec<u8>,
+{
+    let (mut server, mut clients) = registration(config, rng)?;
+    let mut client = clients.remove(0);
+
+    let m1 = round_1(&mut client, config, rng)?;
+    let m1 = decode_m1(config, &relay(1, encode_m1(config, &m1)))?;
+    let handle = client.send_m1(m1, &mut server);
+
+    if last >= 2 {
+        let m2 = round_2(&mut server, config, handle, rng)?;
+        let m2 = decode_m2(config, &relay(2, encode_m2(config, &m2)))?;
+        server.send_m2(m2, &mut client);
+    }
+    if last >= 3 {
+        let m3 = round_3(&mut client, config, rng, false)?;
+        let m3 = decode_m3(config, &relay(3, encode_m3(config, &m3)))?;
+        if m3.1 != handle {
+            return Err(AkeError::Decoding("m3 session handle"));
+        }
+        client.send_m3(m3, &mut server);
+    }
+    if last >= 4 {
+        let m4 = round_4(&mut server, config, handle)?;
+        let m4 = decode_m4(config, &relay(4, encode_m4(config, &m4)))?;
+        server.send_m4(m4, &mut client);
+    }
+    if last >= 5 {
+        let m5 = round_5(&mut client, config, rng, false)?;
+        let m5 = decode_m5(config, &relay(5, encode_m5(config, &m5)))?;
+        if m5.2 != handle {
+            return Err(AkeError::Decoding("m5 session handle"));
+        }
+        client.send_m5(m5, &mut server);
+    }
+    if last >= 6 {
+        round_6(&mut server, config, handle, false)?;
+    }
+
+    Ok((client, server, handle))
+}
+
+pub fn run_rounds<S: Suite>(
+    config: &S,
+    last: usize,
+) -> Result<(Client<S>, Server<S>, SessionHandle)> {
+    run_rounds_with(config, &mut OsRng, last, |_, bytes| bytes)
+}
+
+// Rounds 1 to 6, after which both sides hold the session key and sid
+pub fn handshake<S: Suite>(config: &S) -> (Client<S>, Server<S>, SessionHandle) {
+    run_rounds(config, 6).unwrap()
+}
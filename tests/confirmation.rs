@@ -0,0 +1,118 @@
+mod common;
+
+use generic_anon_ake::{
+    classic,
+    common::{
+        error::AkeError,
+        protocol::{round_7, round_8, round_9},
+        suite::Suite,
+        wire::{decode_m6, decode_m7, encode_m6, encode_m7},
+    },
+    pq::{
+        self,
+        supported_algs::{get_kem_algorithm, get_signature_algorithm},
+    },
+};
+
+use common::handshake;
+
+fn both_sides_confirm<S: Suite>(config: &S) {
+    let (mut client, mut server, handle) = handshake(config);
+    assert!(!client.is_confirmed());
+    assert!(!server.is_confirmed(handle).unwrap());
+
+    let m6 = round_7(&mut server, config, handle).unwrap();
+    let m6 = decode_m6(config, &encode_m6(config, &m6)).unwrap();
+    server.send_m6(m6, &mut client);
+    // The server is only confirmed by the client finished message
+    assert!(!server.is_confirmed(handle).unwrap());
+
+    let m7 = round_8(&mut client, config, false).unwrap();
+    assert!(client.is_confirmed());
+    assert_eq!(m7.1, handle);
+    let m7 = decode_m7(config, &encode_m7(config, &m7)).unwrap();
+    client.send_m7(m7, &mut server);
+
+    round_9(&mut server, config, handle, false).unwrap();
+    assert!(server.is_confirmed(handle).unwrap());
+    assert_eq!(
+        client.get_transcript(),
+        server.get_transcript(handle).unwrap()
+    );
+}
+
+#[test]
+fn classic_sides_confirm_the_key() {
+    both_sides_confirm(&classic::config::Config::new(3));
+}
+
+#[test]
+fn pq_sides_confirm_the_key() {
+    let kemalg = get_kem_algorithm("Kyber512").unwrap();
+    let sigalg = get_signature_algorithm("Dilithium2").unwrap();
+    both_sides_confirm(&pq::config::Config::new(3, kemalg, sigalg));
+}
+
+#[test]
+fn client_rejects_a_wrong_server_finished_tag() {
+    let config = classic::config::Config::new(2);
+    let (mut client, mut server, handle) = handshake(&config);
+
+    let mut m6 = round_7(&mut server, &config, handle).unwrap();
+    m6[0] ^= 0x01;
+    server.send_m6(m6, &mut client);
+
+    assert_eq!(
+        round_8(&mut client, &config, false),
+        Err(AkeError::BadConfirmation)
+    );
+    assert!(!client.is_confirmed());
+}
+
+#[test]
+fn server_rejects_a_wrong_client_finished_tag() {
+    let config = classic::config::Config::new(2);
+
+    // The server finished tag replayed as the client one, then a truncated tag
+    for replay in [true, false] {
+        let (mut client, mut server, handle) = handshake(&config);
+
+        let m6 = round_7(&mut server, &config, handle).unwrap();
+        server.send_m6(m6.clone(), &mut client);
+        let (tag, _) = round_8(&mut client, &config, false).unwrap();
+
+        let wrong = if replay {
+            m6
+        } else {
+            tag[..tag.len() - 1].to_vec()
+        };
+        client.send_m7((wrong, handle), &mut server);
+        assert_eq!(
+            round_9(&mut server, &config, handle, false),
+            Err(AkeError::BadConfirmation)
+        );
+
+        // The session is dropped, the right tag comes too late
+        assert!(!server.has_session(handle));
+        client.send_m7((tag, handle), &mut server);
+        assert_eq!(
+            round_9(&mut server, &config, handle, false),
+            Err(AkeError::MissingState("session for this handle"))
+        );
+    }
+}
+
+#[test]
+fn confirmation_needs_the_finished_messages() {
+    let config = classic::config::Config::new(2);
+    let (mut client, mut server, handle) = handshake(&config);
+
+    assert_eq!(
+        round_8(&mut client, &config, false),
+        Err(AkeError::MissingState("server finished tag"))
+    );
+    assert_eq!(
+        round_9(&mut server, &config, handle, false),
+        Err(AkeError::MissingState("client finished tag"))
+    );
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use generic_anon_ake::{
+    classic,
+    common::suite::Suite,
+    hybrid,
+    pq::{
+        self,
+        supported_algs::{get_kem_algorithm, get_signature_algorithm},
+    },
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+use common::run_rounds_with;
+
+// Encoded m1, ..., m5 followed by the session key and sid of both sides
+fn transcript<S: Suite>(config: &S, seed: u64) -> Vec<Vec<u8>> {
+    let mut messages = Vec::new();
+    let relay = |_, bytes: Vec<u8>| {
+        messages.push(bytes.clone());
+        bytes
+    };
+    let (client, server, handle) =
+        run_rounds_with(config, &mut StdRng::seed_from_u64(seed), 6, relay).unwrap();
+
+    messages.extend([
+        client.get_key().unwrap().to_vec(),
+        client.get_sid().unwrap().to_vec(),
+        server.get_key(handle).unwrap().to_vec(),
+        server.get_sid(handle).unwrap().to_vec(),
+    ]);
+    messages
+}
+
+#[test]
+fn classic_handshake_is_reproducible_from_a_seed() {
+    let config = classic::config::Config::new(3);
+
+    let first = transcript(&config, 7);
+    let second = transcript(&config, 7);
+    let other = transcript(&config, 8);
+
+    assert_eq!(first, second);
+    assert_eq!(first[5], first[7]);
+    assert_ne!(first[0], other[0]);
+    assert_ne!(first[5], other[5]);
+}
+
+#[test]
+fn pq_handshake_is_reproducible_from_a_seed() {
+    let kemalg = get_kem_algorithm("Kyber512").unwrap();
+    let sigalg = get_signature_algorithm("Dilithium2").unwrap();
+    let config = pq::config::Config::new(3, kemalg, sigalg);
+
+    let first = transcript(&config, 7);
+    let second = transcript(&config, 7);
+    let other = transcript(&config, 8);
+
+    assert_eq!(first, second);
+    assert_eq!(first[5], first[7]);
+    assert_eq!(first[6], first[8]);
+    assert_ne!(first[1], other[1]);
+    assert_ne!(first[5], other[5]);
+}
+
+// Falcon and SPHINCS+ sign with randomness of their own, drawn from the
+// signing seed like that of ML-DSA
+#[test]
+fn pq_handshake_is_reproducible_with_every_signature_family() {
+    for sig in ["ML-DSA-44", "Falcon512", "SphincsShake128fSimple"] {
+        let kemalg = get_kem_algorithm("ML-KEM-512").unwrap();
+        let sigalg = get_signature_algorithm(sig).unwrap();
+        let config = pq::config::Config::new(2, kemalg, sigalg);
+
+        assert_eq!(transcript(&config, 3), transcript(&config, 3), "{sig}");
+    }
+}
+
+#[test]
+fn hybrid_handshake_is_reproducible_from_a_seed() {
+    let kemalg = get_kem_algorithm("ML-KEM-768").unwrap();
+    let sigalg = get_signature_algorithm("ML-DSA-65").unwrap();
+    let config = hybrid::config::Config::new(3, kemalg, sigalg);
+
+    let first = transcript(&config, 7);
+
+    assert_eq!(first, transcript(&config, 7));
+    assert_ne!(first[1], transcript(&config, 8)[1]);
+}
@@ -0,0 +1,167 @@
+mod common;
+
+use generic_anon_ake::{
+    common::{
+        error::AkeError,
+        suite::{CcaPke, Pke, Sig, Suite},
+        utils::get_random_key32,
+        wire::{peek_header, MessageType},
+    },
+    hybrid::{config::Config, config::SUITE_ID},
+    pq::supported_algs::{get_kem_algorithm, get_signature_algorithm},
+};
+use rand::rngs::OsRng;
+
+use common::run_rounds_with;
+
+fn config(users: u32) -> Config {
+    let kemalg = get_kem_algorithm("Kyber768").unwrap();
+    let sigalg = get_signature_algorithm("Dilithium3").unwrap();
+    Config::new(users, kemalg, sigalg)
+}
+
+// PKE coins as drawn in round 2 for recipient 0
+fn coins(config: &Config) -> Vec<u8> {
+    let r = config.pke().pke_randomness(&mut OsRng).unwrap();
+    config.pke_coins(&r, 0).unwrap()
+}
+
+#[test]
+fn hybrid_pke_is_deterministic() {
+    let config = config(1);
+    let (pk, sk) = config.pke().pke_gen(&mut OsRng).unwrap();
+    let m = get_random_key32();
+    let r = coins(&config);
+
+    let ct1 = config.pke().pke_enc(&pk, &m, &r).unwrap();
+    let ct2 = config.pke().pke_enc(&pk, &m, &r).unwrap();
+    let ct3 = config.pke().pke_enc(&pk, &m, &coins(&config)).unwrap();
+
+    assert_eq!(config.pke().pke_dec(&sk, &ct1).unwrap(), m);
+    assert!(config.pke().check_ciphertext(&ct1, &ct2));
+    assert!(!config.pke().check_ciphertext(&ct1, &ct3));
+}
+
+#[test]
+fn hybrid_ciphertexts_need_both_halves() {
+    let config = config(1);
+    let (pk, sk) = config.pke().pke_gen(&mut OsRng).unwrap();
+    let m = get_random_key32();
+    let ct = config.pke().pke_enc(&pk, &m, &coins(&config)).unwrap();
+    let other = config.pke().pke_enc(&pk, &m, &coins(&config)).unwrap();
+
+    // ECIES half of one ciphertext with the KEM half of another
+    let mixed = (other.0.clone(), ct.1.clone(), ct.2.clone(), ct.3);
+    assert!(config.pke().pke_dec(&sk, &mixed).is_err());
+    let mixed = (ct.0.clone(), other.1.clone(), ct.2.clone(), ct.3);
+    assert!(config.pke().pke_dec(&sk, &mixed).is_err());
+
+    // A key pair with a different ECIES key does not decrypt
+    let (_, sk2) = config.pke().pke_gen(&mut OsRng).unwrap();
+    assert!(config.pke().pke_dec(&(sk2.0, sk.1.clone()), &ct).is_err());
+}
+
+#[test]
+fn hybrid_ccapke_works() {
+    let config = config(1);
+    let (pk, sk) = config.ccapke().ccapke_gen(&mut OsRng).unwrap();
+    let m = get_random_key32();
+
+    let ct1 = config.ccapke().ccapke_enc(&pk, &m, &mut OsRng).unwrap();
+    let ct2 = config.ccapke().ccapke_enc(&pk, &m, &mut OsRng).unwrap();
+    let bytes = CcaPke::ciphertext_to_bytes(config.ccapke(), &ct1);
+    let decoded = CcaPke::ciphertext_from_bytes(config.ccapke(), &bytes).unwrap();
+
+    assert_eq!(config.ccapke().ccapke_dec(&sk, &decoded).unwrap(), m);
+    assert_ne!(bytes, CcaPke::ciphertext_to_bytes(config.ccapke(), &ct2));
+    assert!(CcaPke::ciphertext_from_bytes(config.ccapke(), &bytes[1..]).is_err());
+
+    let mut tampered = ct1.clone();
+    tampered.2[0] ^= 0x01;
+    assert_eq!(
+        config.ccapke().ccapke_dec(&sk, &tampered),
+        Err(AkeError::Verification("CCA-PKE ciphertext"))
+    );
+}
+
+#[test]
+fn hybrid_keys_round_trip() {
+    let config = config(1);
+    let (pk, sk) = config.pke().pke_gen(&mut OsRng).unwrap();
+    let pk_bytes = Pke::public_key_to_bytes(config.pke(), &pk);
+    let sk_bytes = config.pke().secret_key_to_bytes(&sk);
+    assert_eq!(
+        Pke::public_key_to_bytes(
+            config.pke(),
+            &Pke::public_key_from_bytes(config.pke(), &pk_bytes).unwrap()
+        ),
+        pk_bytes
+    );
+    assert_eq!(
+        config
+            .pke()
+            .secret_key_to_bytes(&config.pke().secret_key_from_bytes(&sk_bytes).unwrap()),
+        sk_bytes
+    );
+
+    let (pk, sk) = config.sig().sig_gen(&mut OsRng).unwrap();
+    let pk_bytes = config.sig().public_key_to_bytes(&pk);
+    let sk_bytes = config.sig().secret_key_to_bytes(&sk);
+    let pk2 = config.sig().public_key_from_bytes(&pk_bytes).unwrap();
+    let sk2 = config.sig().secret_key_from_bytes(&sk_bytes).unwrap();
+    let m = get_random_key32();
+    assert!(config
+        .sig()
+        .sig_vry(&pk2, &m, &config.sig().sig_sign(&sk2, &m).unwrap()));
+}
+
+#[test]
+fn dual_signature_needs_both_halves() {
+    let config = config(1);
+    let (pk, sk) = config.sig().sig_gen(&mut OsRng).unwrap();
+    let (pk2, sk2) = config.sig().sig_gen(&mut OsRng).unwrap();
+    let m = get_random_key32();
+
+    let signature = config.sig().sig_sign(&sk, &m).unwrap();
+    let signature2 = config.sig().sig_sign(&sk2, &m).unwrap();
+    assert!(config.sig().sig_vry(&pk, &m, &signature));
+    assert!(!config.sig().sig_vry(&pk2, &m, &signature));
+
+    // A valid ECDSA half does not save a foreign PQ half, nor the other way
+    let mixed = (signature.0, signature2.1.clone());
+    assert!(!config.sig().sig_vry(&pk, &m, &mixed));
+    let mixed = (signature2.0, signature.1.clone());
+    assert!(!config.sig().sig_vry(&pk, &m, &mixed));
+    assert!(!config.sig().sig_vry(&(pk.0, pk2.1), &m, &signature));
+}
+
+#[test]
+fn hybrid_handshake_works() {
+    let config = config(3);
+    let relay = |index: usize, bytes: Vec<u8>| {
+        if index == 2 {
+            assert_eq!(peek_header(&bytes).unwrap(), (1, SUITE_ID, MessageType::M2));
+        }
+        bytes
+    };
+
+    let (client, server, handle) = run_rounds_with(&config, &mut OsRng, 6, relay).unwrap();
+
+    assert_eq!(client.get_key().unwrap(), server.get_key(handle).unwrap());
+    assert_eq!(client.get_sid().unwrap(), server.get_sid(handle).unwrap());
+}
+
+#[test]
+fn algorithm_names_show_both_halves() {
+    let config = config(1);
+
+    assert_eq!(
+        Pke::algorithm_name(config.pke()),
+        "ECIES-secp256k1+Kyber768"
+    );
+    assert_eq!(
+        CcaPke::algorithm_name(config.ccapke()),
+        "ECIES-secp256k1+Kyber768"
+    );
+    assert_eq!(config.sig().algorithm_name(), "ECDSA-secp256k1+Dilithium3");
+}
@@ -0,0 +1,226 @@
+use std::{
+    env, fs,
+    io::{self, Cursor, Write},
+    net::{TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use generic_anon_ake::{
+    classic,
+    common::{
+        error::AkeError,
+        net::{connect_session, recv_frame, serve_session, serve_with_timeout, MAX_FRAME_LENGTH},
+        store::{
+            client_keygen_to_file, load_client, load_client_public_key, load_registry, load_server,
+            public_key_path, register_client,
+        },
+        suite::{Pke, Suite},
+    },
+    pq::{
+        self,
+        supported_algs::{get_kem_algorithm, get_signature_algorithm},
+    },
+};
+use rand::rngs::OsRng;
+
+// Every client generates its key pair locally and the server registers the
+// public halves. Returns the client key files in registration order.
+fn register_clients<S: Suite>(config: &S, dir: &Path) -> Vec<PathBuf> {
+    fs::create_dir_all(dir).unwrap();
+    (0..config.get_users_number())
+        .map(|i| {
+            let key = dir.join(format!("client-{i}.key"));
+            client_keygen_to_file(config, &key).unwrap();
+            let vk = load_client_public_key(config, &public_key_path(&key)).unwrap();
+            assert_eq!(register_client(config, dir, &vk).unwrap(), i);
+            key
+        })
+        .collect()
+}
+
+fn state_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("generic-anon-ake-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+// Registers the clients on disk, then runs one handshake for client `id` over
+// a localhost socket with the server in another thread
+fn handshake_over_tcp<S, F>(new_config: F, id: u32, name: &str, confirm: bool)
+where
+    S: Suite,
+    F: Fn() -> S + Send + Copy + 'static,
+{
+    let dir = state_dir(name);
+    let config = new_config();
+    let keys = register_clients(&config, &dir);
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let server_dir = dir.clone();
+
+    let server_thread = thread::spawn(move || {
+        let config = new_config();
+        let mut server = load_server(&config, &server_dir).unwrap();
+        let (mut stream, _) = listener.accept().unwrap();
+        let handle = serve_session(
+            &mut server,
+            &config,
+            &mut stream,
+            &mut OsRng,
+            confirm,
+            false,
+        )
+        .unwrap();
+
+        (
+            handle,
+            server.get_key(handle).unwrap().to_vec(),
+            server.get_sid(handle).unwrap().to_vec(),
+            server.is_confirmed(handle).unwrap(),
+        )
+    });
+
+    let mut client = load_client(&config, &keys[id as usize], &dir).unwrap();
+    assert_eq!(client.get_id(), id);
+    let mut stream = TcpStream::connect(address).unwrap();
+    connect_session(
+        &mut client,
+        &config,
+        &mut stream,
+        &mut OsRng,
+        confirm,
+        false,
+    )
+    .unwrap();
+
+    let (handle, key, sid, confirmed) = server_thread.join().unwrap();
+    assert_eq!(client.get_handle().unwrap(), handle);
+    assert_eq!(client.get_key().unwrap(), key);
+    assert_eq!(client.get_sid().unwrap(), sid);
+    assert_eq!(client.is_confirmed(), confirm);
+    assert_eq!(confirmed, confirm);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+fn pq_config() -> pq::config::Config {
+    let kemalg = get_kem_algorithm("Kyber768").unwrap();
+    let sigalg = get_signature_algorithm("Dilithium3").unwrap();
+    pq::config::Config::new(3, kemalg, sigalg)
+}
+
+fn classic_config() -> classic::config::Config {
+    classic::config::Config::new(3)
+}
+
+#[test]
+fn handshake_over_tcp_pq() {
+    handshake_over_tcp(pq_config, 1, "pq", false);
+}
+
+#[test]
+fn handshake_over_tcp_classic() {
+    handshake_over_tcp(classic_config, 2, "classic", false);
+}
+
+#[test]
+fn confirmed_handshake_over_tcp_pq() {
+    handshake_over_tcp(pq_config, 0, "pq-confirm", true);
+}
+
+#[test]
+fn confirmed_handshake_over_tcp_classic() {
+    handshake_over_tcp(classic_config, 1, "classic-confirm", true);
+}
+
+#[test]
+fn registry_holds_only_public_keys() {
+    let config = classic_config();
+    let dir = state_dir("registry");
+    let keys = register_clients(&config, &dir);
+
+    // Registering again does not add a second entry
+    let vk = load_client_public_key(&config, &public_key_path(&keys[1])).unwrap();
+    assert_eq!(register_client(&config, &dir, &vk).unwrap(), 1);
+
+    let vks = load_registry(&config, &dir).unwrap();
+    assert_eq!(vks.len(), 3);
+    let registry = fs::read(dir.join("registry")).unwrap();
+    // version, suite, file type and algorithm name come first
+    let header = 3 + 4 + config.pke().algorithm_name().len();
+    let expected: usize = header
+        + 4
+        + vks
+            .iter()
+            .map(|vk| 4 + Pke::public_key_to_bytes(config.pke(), vk).len())
+            .sum::<usize>();
+    assert_eq!(registry.len(), expected);
+
+    let server = load_server(&config, &dir).unwrap();
+    assert_eq!(server.get_clients_keys().len(), 3);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn load_client_rejects_other_registry_size() {
+    let dir = state_dir("registry-size");
+    let keys = register_clients(&classic_config(), &dir);
+
+    let other = classic::config::Config::new(4);
+    assert!(load_client(&other, &keys[1], &dir).is_err());
+    assert!(load_server(&other, &dir).is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn recv_frame_does_not_trust_the_length() {
+    let mut frame = (MAX_FRAME_LENGTH as u32).to_be_bytes().to_vec();
+    frame.extend_from_slice(b"abc");
+
+    assert_eq!(
+        recv_frame(&mut Cursor::new(frame)),
+        Err(AkeError::Io(io::ErrorKind::UnexpectedEof))
+    );
+}
+
+// Peers that drop the connection, send garbage or stay idle do not stop the
+// server from serving the next client
+#[test]
+fn serve_survives_bad_and_idle_peers() {
+    let dir = state_dir("serve-loop");
+    let config = classic_config();
+    let keys = register_clients(&config, &dir);
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let mut server = load_server(&config, &dir).unwrap();
+    thread::spawn(move || {
+        let config = classic_config();
+        serve_with_timeout(
+            &mut server,
+            &config,
+            &listener,
+            Duration::from_millis(200),
+            false,
+            false,
+        )
+    });
+
+    drop(TcpStream::connect(address).unwrap());
+    let mut garbage = TcpStream::connect(address).unwrap();
+    garbage.write_all(&[0xff; 8]).unwrap();
+    drop(garbage);
+    let _idle = TcpStream::connect(address).unwrap();
+
+    let mut client = load_client(&config, &keys[0], &dir).unwrap();
+    let mut stream = TcpStream::connect(address).unwrap();
+    connect_session(&mut client, &config, &mut stream, &mut OsRng, false, false).unwrap();
+    assert!(client.get_key().is_ok());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
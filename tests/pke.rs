@@ -1,8 +1,23 @@
 use generic_anon_ake::{
     common::utils::get_random_key32,
-    pq::pke::{check_ciphertext, pke_dec, pke_enc},
+    pq::{
+        ccapke::{ccapke_dec, ccapke_enc},
+        pke::{check_ciphertext, encapsulate, pke_dec, pke_enc, DEM_NONCE_LENGTH},
+        seeded::SEED_LENGTH,
+        supported_algs::get_kem_algorithm,
+    },
 };
 use oqs::kem::{self};
+use rand::rngs::OsRng;
+
+// KEM seed || DEM nonce
+fn coins() -> Vec<u8> {
+    [
+        get_random_key32()[..SEED_LENGTH].to_vec(),
+        get_random_key32()[..DEM_NONCE_LENGTH].to_vec(),
+    ]
+    .concat()
+}
 
 #[test]
 fn pke_works() {
@@ -10,15 +25,15 @@ fn pke_works() {
         let kemalg = kem::Kem::new(kem::Algorithm::Kyber1024).unwrap();
         let (pk, sk) = kemalg.keypair().unwrap();
         let m: Vec<u8> = get_random_key32();
-        let r: Vec<u8> = get_random_key32();
-        let r1: Vec<u8> = get_random_key32();
-        let (ct_kem, ct_dem, iv_tag) = pke_enc(&kemalg, &pk, &m, &r);
-        let (ct_kem2, ct_dem2, iv_tag2) = pke_enc(&kemalg, &pk, &m, &r);
-        let ct1 = pke_enc(&kemalg, &pk, &m, &r);
-        let ct2 = pke_enc(&kemalg, &pk, &m, &r);
-        let ct3 = pke_enc(&kemalg, &pk, &m, &r1);
-
-        let m_decrypted = pke_dec(&kemalg, sk, &ct_kem, &ct_dem, &iv_tag);
+        let r: Vec<u8> = coins();
+        let r1: Vec<u8> = coins();
+        let (ct_kem, ct_dem, iv_tag) = pke_enc(&kemalg, &pk, &m, &r).unwrap();
+        let (ct_kem2, ct_dem2, iv_tag2) = pke_enc(&kemalg, &pk, &m, &r).unwrap();
+        let ct1 = pke_enc(&kemalg, &pk, &m, &r).unwrap();
+        let ct2 = pke_enc(&kemalg, &pk, &m, &r).unwrap();
+        let ct3 = pke_enc(&kemalg, &pk, &m, &r1).unwrap();
+
+        let m_decrypted = pke_dec(&kemalg, &sk, &ct_kem, &ct_dem, &iv_tag).unwrap();
         assert_eq!(m, m_decrypted);
         assert_eq!(ct_kem, ct_kem2);
         assert_eq!(ct_dem, ct_dem2);
@@ -33,13 +48,13 @@ fn pke_works_with_classic_mceliece() {
     let kemalg = kem::Kem::new(kem::Algorithm::ClassicMcEliece6960119f).unwrap();
     let (pk, sk) = kemalg.keypair().unwrap();
     let m: Vec<u8> = get_random_key32();
-    let r: Vec<u8> = kemalg.get_randomness().unwrap();
-    let (ct_kem, ct_dem, iv_tag) = pke_enc(&kemalg, &pk, &m, &r);
-    let (ct_kem2, ct_dem2, iv_tag2) = pke_enc(&kemalg, &pk, &m, &r);
-    let ct1 = pke_enc(&kemalg, &pk, &m, &r);
-    let ct2 = pke_enc(&kemalg, &pk, &m, &r);
+    let r: Vec<u8> = coins();
+    let (ct_kem, ct_dem, iv_tag) = pke_enc(&kemalg, &pk, &m, &r).unwrap();
+    let (ct_kem2, ct_dem2, iv_tag2) = pke_enc(&kemalg, &pk, &m, &r).unwrap();
+    let ct1 = pke_enc(&kemalg, &pk, &m, &r).unwrap();
+    let ct2 = pke_enc(&kemalg, &pk, &m, &r).unwrap();
 
-    let m_decrypted = pke_dec(&kemalg, sk, &ct_kem, &ct_dem, &iv_tag);
+    let m_decrypted = pke_dec(&kemalg, &sk, &ct_kem, &ct_dem, &iv_tag).unwrap();
     assert_eq!(m, m_decrypted);
     assert_eq!(ct_kem, ct_kem2);
     assert_eq!(ct_dem, ct_dem2);
@@ -53,13 +68,116 @@ fn pke_is_deterministic() {
 
     let (pk, sk) = kemalg.keypair().unwrap();
 
-    let r = get_random_key32();
+    let r = coins();
     let m = get_random_key32();
 
-    let ct = pke_enc(&kemalg, &pk, &m, &r);
-    let ct2 = pke_enc(&kemalg, &pk, &m, &r);
-    let m2 = pke_dec(&kemalg, sk, &ct2.0, &ct.1, &ct.2);
+    let ct = pke_enc(&kemalg, &pk, &m, &r).unwrap();
+    let ct2 = pke_enc(&kemalg, &pk, &m, &r).unwrap();
+    let m2 = pke_dec(&kemalg, &sk, &ct2.0, &ct.1, &ct.2).unwrap();
 
     assert_eq!(ct, ct2);
     assert_eq!(m, m2);
 }
+
+#[test]
+fn pke_uses_the_dem_nonce_from_the_coins() {
+    let kemalg = kem::Kem::new(kem::Algorithm::Kyber768).unwrap();
+    let (pk, _) = kemalg.keypair().unwrap();
+    let m = get_random_key32();
+    let r = coins();
+
+    let (_, _, iv) = pke_enc(&kemalg, &pk, &m, &r).unwrap();
+
+    assert_eq!(iv.as_slice(), &r[r.len() - DEM_NONCE_LENGTH..]);
+    assert!(pke_enc(&kemalg, &pk, &m, &r[..DEM_NONCE_LENGTH]).is_err());
+}
+
+#[test]
+fn encapsulation_is_a_function_of_the_seed() {
+    for name in [
+        "ML-KEM-768",
+        "Kyber768",
+        "BikeL1",
+        "BikeL5",
+        "Hqc128",
+        "FrodoKem640Aes",
+        "FrodoKem976Shake",
+    ] {
+        let kemalg = get_kem_algorithm(name).unwrap();
+        let (pk, sk) = kemalg.keypair().unwrap();
+        let seed = get_random_key32();
+        let other = get_random_key32();
+
+        let (ct, k) = encapsulate(&kemalg, &pk, &seed).unwrap();
+        let (ct2, k2) = encapsulate(&kemalg, &pk, &seed).unwrap();
+        let (ct3, _) = encapsulate(&kemalg, &pk, &other).unwrap();
+
+        assert_eq!((&ct, &k), (&ct2, &k2), "{name}");
+        assert_ne!(ct, ct3, "{name}");
+        assert_eq!(kemalg.decapsulate(&sk, &ct).unwrap(), k, "{name}");
+        assert!(encapsulate(&kemalg, &pk, &seed[..SEED_LENGTH - 1]).is_err());
+        // Outside of a seeded call liboqs samples fresh randomness again
+        assert_ne!(
+            kemalg.encapsulate(&pk).unwrap().0,
+            kemalg.encapsulate(&pk).unwrap().0,
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn truncated_dem_ciphertexts_do_not_match() {
+    let kemalg = kem::Kem::new(kem::Algorithm::Kyber512).unwrap();
+    let (pk, _) = kemalg.keypair().unwrap();
+    let m = get_random_key32();
+    let r = coins();
+
+    let ct = pke_enc(&kemalg, &pk, &m, &r).unwrap();
+    let mut truncated = ct.clone();
+    truncated.1.pop();
+    let mut empty = ct.clone();
+    empty.1.clear();
+
+    assert!(!check_ciphertext(&ct, &truncated));
+    assert!(!check_ciphertext(&truncated, &ct));
+    assert!(!check_ciphertext(&ct, &empty));
+}
+
+// Both roles of each code-based and FrodoKEM parameter set: the PKE with the
+// derandomised encapsulation and the CCA-PKE. FrodoKEM-640/976 and HQC have
+// shared secrets of other lengths than the 32 bytes of the DEM key.
+#[test]
+fn pke_and_ccapke_work_with_hqc_bike_and_frodokem() {
+    for name in [
+        "BikeL1",
+        "BikeL3",
+        "BikeL5",
+        "Hqc128",
+        "Hqc192",
+        "Hqc256",
+        "FrodoKem640Aes",
+        "FrodoKem640Shake",
+        "FrodoKem976Aes",
+        "FrodoKem976Shake",
+        "FrodoKem1344Aes",
+        "FrodoKem1344Shake",
+    ] {
+        let kemalg = get_kem_algorithm(name).unwrap();
+        let (pk, sk) = kemalg.keypair().unwrap();
+        let m: Vec<u8> = get_random_key32();
+        let r: Vec<u8> = coins();
+        let r1: Vec<u8> = coins();
+
+        let ct1 = pke_enc(&kemalg, &pk, &m, &r).unwrap();
+        let ct2 = pke_enc(&kemalg, &pk, &m, &r).unwrap();
+        let ct3 = pke_enc(&kemalg, &pk, &m, &r1).unwrap();
+        let m_decrypted = pke_dec(&kemalg, &sk, &ct1.0, &ct1.1, &ct1.2).unwrap();
+        assert_eq!(m, m_decrypted, "{name}");
+        assert!(check_ciphertext(&ct1, &ct2), "{name}");
+        assert!(!check_ciphertext(&ct1, &ct3), "{name}");
+
+        let (ct_kem, ct_dem, iv_tag) = ccapke_enc(&kemalg, &pk, &m, &mut OsRng).unwrap();
+        let m_decrypted = ccapke_dec(&kemalg, &sk, &ct_kem, &ct_dem, &iv_tag).unwrap();
+        assert_eq!(m, m_decrypted, "{name}");
+    }
+}
@@ -1,20 +1,79 @@
 use generic_anon_ake::{
-    classic::pke::{pke_dec, pke_enc, pke_gen},
+    classic::{
+        ecies::{NONCE_LENGTH, PUBLIC_KEY_LENGTH, TAG_LENGTH},
+        pke::{check_ciphertext, pke_dec, pke_enc, pke_gen},
+    },
     common::utils::get_random_key32,
 };
+use rand::rngs::OsRng;
 
 #[test]
 fn pke_classic_works() {
-    let (pk, sk) = pke_gen();
+    let (pk, sk) = pke_gen(&mut OsRng);
 
     let m = get_random_key32();
     let r: Vec<u8> = (0_u8..16_u8).collect();
-    let ct = pke_enc(&pk, &m, &r.as_slice());
-    let ct2 = pke_enc(&pk, &m, &r);
+    let ct = pke_enc(&pk, &m, r.as_slice()).unwrap();
+    let ct2 = pke_enc(&pk, &m, &r).unwrap();
 
     assert_eq!(ct, ct2);
 
-    let m2 = pke_dec(&sk, &ct);
+    let m2 = pke_dec(&sk, &ct).unwrap();
 
     assert_eq!(m, m2);
 }
+
+#[test]
+fn truncated_ciphertexts_do_not_match() {
+    let (pk, _) = pke_gen(&mut OsRng);
+    let m = get_random_key32();
+    let r: Vec<u8> = (0_u8..16_u8).collect();
+    let ct = pke_enc(&pk, &m, &r).unwrap();
+
+    assert!(check_ciphertext(&ct, &ct.clone()));
+    assert!(!check_ciphertext(&ct, &ct[..ct.len() - 1]));
+    assert!(!check_ciphertext(&ct[..1], &ct));
+    assert!(!check_ciphertext(&ct, &[]));
+}
+
+// eph_pk (65) || nonce (16) || tag (16) || ciphertext, as in ecies-rs
+#[test]
+fn ciphertexts_have_the_ecies_layout() {
+    let (pk, sk) = pke_gen(&mut OsRng);
+    let r = get_random_key32();
+
+    for length in [32, 64] {
+        let m = vec![0x5a; length];
+        let ct = pke_enc(&pk, &m, &r).unwrap();
+
+        assert_eq!(
+            ct.len(),
+            PUBLIC_KEY_LENGTH + NONCE_LENGTH + TAG_LENGTH + length
+        );
+        assert_eq!(pke_dec(&sk, &ct).unwrap(), m);
+    }
+}
+
+#[test]
+fn tampered_or_short_ciphertexts_are_rejected() {
+    let (pk, sk) = pke_gen(&mut OsRng);
+    let (_, other) = pke_gen(&mut OsRng);
+    let m = get_random_key32();
+    let ct = pke_enc(&pk, &m, &get_random_key32()).unwrap();
+
+    for position in [
+        PUBLIC_KEY_LENGTH,
+        PUBLIC_KEY_LENGTH + NONCE_LENGTH,
+        ct.len() - 1,
+    ] {
+        let mut tampered = ct.clone();
+        tampered[position] ^= 0x01;
+        assert!(pke_dec(&sk, &tampered).is_err(), "{position}");
+    }
+    assert!(pke_dec(&other, &ct).is_err());
+    assert!(pke_dec(
+        &sk,
+        &ct[..PUBLIC_KEY_LENGTH + NONCE_LENGTH + TAG_LENGTH - 1]
+    )
+    .is_err());
+}
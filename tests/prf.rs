@@ -1,4 +1,8 @@
-use generic_anon_ake::common::{prf::prf, utils::get_random_key32};
+use generic_anon_ake::common::{
+    prf::{kmac256, prf, AesCtrPrf, Kmac256Prf, PrfAlgorithm, Shake256Prf},
+    suite::Prf,
+    utils::get_random_key32,
+};
 
 #[test]
 fn prf_works() {
@@ -8,9 +12,9 @@ fn prf_works() {
         let nonce = counter.to_be_bytes();
         counter += 1;
         let nonce2 = counter.to_be_bytes();
-        let n = prf(&key, &nonce);
-        let n2 = prf(&key, &nonce2);
-        let n3 = prf(&key, &nonce);
+        let n = prf(&key, &nonce).unwrap();
+        let n2 = prf(&key, &nonce2).unwrap();
+        let n3 = prf(&key, &nonce).unwrap();
 
         assert_ne!(n, n2);
         assert_eq!(n, n3);
@@ -19,3 +23,85 @@ fn prf_works() {
         assert!(n3.len() == 16);
     }
 }
+
+fn kat_key() -> Vec<u8> {
+    (0u8..32).collect()
+}
+
+#[test]
+fn aes_ctr_prf_known_answer() {
+    let out = AesCtrPrf.prf(&kat_key(), b"pke coins", 5, 48).unwrap();
+
+    assert_eq!(
+        hex::encode(out),
+        "94eb04de7f6632a4ce5229244ac9150e092338416f7d40f79a5201a24f9f3f36\
+         c22cf0a7e6eb56391683c19acf0bc863"
+    );
+}
+
+#[test]
+fn shake256_prf_known_answer() {
+    let out = Shake256Prf.prf(&kat_key(), b"pke coins", 5, 48).unwrap();
+
+    assert_eq!(
+        hex::encode(out),
+        "6696fa8c414a27f08c41df54562720dcf696b6d1cbbda243ee486e9666e6fd96\
+         81b354b3a0228c51e02707992f52f573"
+    );
+
+    // Outputs as long as McEliece coins
+    let long = Shake256Prf.prf(&kat_key(), b"x", 0, 200).unwrap();
+    assert_eq!(
+        hex::encode(&long[184..]),
+        "50d6b739454dd8744a7d7cfa46b8344a"
+    );
+}
+
+// NIST SP 800-185 KMAC256 samples #4 and #6
+#[test]
+fn kmac256_known_answer() {
+    let key: Vec<u8> = (0x40u8..0x60).collect();
+
+    let out = kmac256(&key, &[0, 1, 2, 3], 64, b"My Tagged Application");
+    assert_eq!(
+        hex::encode(out),
+        "20c570c31346f703c9ac36c61c03cb64c3970d0cfc787e9b79599d273a68d2f7\
+         f69d4cc3de9d104a351689f27cf6f5951f0103f33f4f24871024d9c27773a8dd"
+    );
+
+    let data: Vec<u8> = (0u8..200).collect();
+    let out = kmac256(&key, &data, 64, b"My Tagged Application");
+    assert_eq!(
+        hex::encode(out),
+        "b58618f71f92e1d56c1b8c55ddd7cd188b97b4ca4d99831eb2699a837da2e4d9\
+         70fbacfde50033aea585f1a2708510c32d07880801bd182898fe476876fc8965"
+    );
+
+    assert_eq!(
+        Kmac256Prf.prf(&key, b"label", 7, 40).unwrap(),
+        kmac256(&key, &7u64.to_be_bytes(), 40, b"label")
+    );
+}
+
+#[test]
+fn prfs_separate_labels_indices_and_lengths() {
+    let key = get_random_key32();
+
+    for alg in [
+        PrfAlgorithm::AesCtr,
+        PrfAlgorithm::Shake256,
+        PrfAlgorithm::Kmac256,
+    ] {
+        assert_eq!(PrfAlgorithm::from_name(alg.name()), Some(alg));
+
+        let out = alg.prf(&key, b"a", 0, 64).unwrap();
+        assert_eq!(out.len(), 64);
+        assert_eq!(out, alg.prf(&key, b"a", 0, 64).unwrap());
+        assert_ne!(out, alg.prf(&key, b"b", 0, 64).unwrap());
+        assert_ne!(out, alg.prf(&key, b"a", 1, 64).unwrap());
+
+        // Short keys are accepted, empty ones are not
+        assert_eq!(alg.prf(&key[..16], b"a", 0, 1000).unwrap().len(), 1000);
+        assert!(alg.prf(&[], b"a", 0, 32).is_err());
+    }
+}
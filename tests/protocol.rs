@@ -0,0 +1,177 @@
+mod common;
+
+use generic_anon_ake::{
+    classic,
+    common::{
+        error::AkeError,
+        protocol::{registration, round_1, round_2, round_3, round_4, round_5, round_6},
+        suite::Suite,
+    },
+    pq::{
+        self,
+        supported_algs::{get_kem_algorithm, get_signature_algorithm},
+    },
+};
+use rand::rngs::OsRng;
+
+use common::{handshake, run_rounds};
+
+#[test]
+fn protocol_pq_works() {
+    let kemalg = get_kem_algorithm("Kyber768").unwrap();
+    let sigalg = get_signature_algorithm("Dilithium3").unwrap();
+    let config = pq::config::Config::new(4, kemalg, sigalg);
+
+    let (client, server, handle) = handshake(&config);
+
+    assert_eq!(client.get_key().unwrap(), server.get_key(handle).unwrap());
+    assert_eq!(client.get_sid().unwrap(), server.get_sid(handle).unwrap());
+}
+
+#[test]
+fn protocol_pq_works_with_separate_ccapke_kem() {
+    let kemalg = get_kem_algorithm("ClassicMcEliece348864f").unwrap();
+    let sigalg = get_signature_algorithm("Dilithium2").unwrap();
+    let mut config = pq::config::Config::new(4, kemalg, sigalg);
+    config.set_ccapke_algorithm(get_kem_algorithm("Kyber512").unwrap());
+
+    assert_eq!(
+        config.pke().algorithm(),
+        get_kem_algorithm("ClassicMcEliece348864f")
+            .unwrap()
+            .algorithm()
+    );
+    assert_eq!(
+        config.ccapke().algorithm(),
+        get_kem_algorithm("Kyber512").unwrap().algorithm()
+    );
+
+    let (client, server, handle) = handshake(&config);
+
+    assert_eq!(client.get_key().unwrap(), server.get_key(handle).unwrap());
+    assert_eq!(client.get_sid().unwrap(), server.get_sid(handle).unwrap());
+}
+
+#[test]
+fn protocol_classic_works() {
+    let config = classic::config::Config::new(4);
+
+    let (client, server, handle) = handshake(&config);
+
+    assert_eq!(client.get_key().unwrap(), server.get_key(handle).unwrap());
+    assert_eq!(client.get_sid().unwrap(), server.get_sid(handle).unwrap());
+}
+
+#[test]
+fn round_6_rejects_truncated_ciphertext() {
+    let config = classic::config::Config::new(2);
+    let (mut client, mut server, _) = run_rounds(&config, 4).unwrap();
+    let (mut ctxi, open_s, handle) = round_5(&mut client, &config, &mut OsRng, false).unwrap();
+    ctxi.truncate(ctxi.len() / 2);
+    client.send_m5((ctxi, open_s, handle), &mut server);
+
+    let res = round_6(&mut server, &config, handle, false);
+
+    assert!(res.is_err());
+    assert!(server.get_key(handle).is_err());
+}
+
+#[test]
+fn rounds_report_missing_state() {
+    let config = classic::config::Config::new(2);
+    let (mut server, mut clients) = registration(&config, &mut OsRng).unwrap();
+    let mut client = clients.remove(0);
+
+    assert!(matches!(
+        round_3(&mut client, &config, &mut OsRng, false),
+        Err(AkeError::MissingState(_))
+    ));
+    assert!(matches!(
+        round_2(&mut server, &config, 42, &mut OsRng),
+        Err(AkeError::MissingState(_))
+    ));
+
+    let m1 = round_1(&mut client, &config, &mut OsRng).unwrap();
+    let handle = client.send_m1(m1, &mut server);
+    assert!(matches!(
+        round_6(&mut server, &config, handle, false),
+        Err(AkeError::MissingState(_))
+    ));
+}
+
+fn run_for_clients<S: Suite>(config: &S, ids: &[u32]) {
+    let (mut server, mut clients) = registration(config, &mut OsRng).unwrap();
+
+    for &id in ids {
+        let client = &mut clients[id as usize];
+        assert_eq!(client.get_id(), id);
+
+        let m1 = round_1(client, config, &mut OsRng).unwrap();
+        let handle = client.send_m1(m1, &mut server);
+        let m2 = round_2(&mut server, config, handle, &mut OsRng).unwrap();
+        server.send_m2(m2, client);
+        let m3 = round_3(client, config, &mut OsRng, false).unwrap();
+        client.send_m3(m3, &mut server);
+        let m4 = round_4(&mut server, config, handle).unwrap();
+        server.send_m4(m4, client);
+        let m5 = round_5(client, config, &mut OsRng, false).unwrap();
+        client.send_m5(m5, &mut server);
+        round_6(&mut server, config, handle, false).unwrap();
+
+        assert_eq!(client.get_handle().unwrap(), handle);
+        assert_eq!(client.get_key().unwrap(), server.get_key(handle).unwrap());
+        assert_eq!(client.get_sid().unwrap(), server.get_sid(handle).unwrap());
+    }
+
+    // Every client ended up with its own session key
+    assert_ne!(
+        clients[ids[0] as usize].get_key().unwrap(),
+        clients[ids[1] as usize].get_key().unwrap()
+    );
+}
+
+#[test]
+fn protocol_pq_works_for_any_client() {
+    let kemalg = get_kem_algorithm("Kyber768").unwrap();
+    let sigalg = get_signature_algorithm("Dilithium3").unwrap();
+    let config = pq::config::Config::new(10, kemalg, sigalg);
+
+    run_for_clients(&config, &[0, 7, 9]);
+}
+
+#[test]
+fn protocol_classic_works_for_any_client() {
+    let config = classic::config::Config::new(10);
+
+    run_for_clients(&config, &[0, 7, 9]);
+}
+
+#[test]
+fn registered_clients_hold_their_own_keys() {
+    let config = classic::config::Config::new(3);
+    let (server, clients) = registration(&config, &mut OsRng).unwrap();
+
+    assert_eq!(clients.len(), 3);
+    for (i, client) in clients.iter().enumerate() {
+        assert_eq!(client.get_id(), i as u32);
+        assert_eq!(client.get_vks().len(), 3);
+        assert!(client.get_ek().is_ok());
+        assert_eq!(client.get_pks().unwrap(), server.get_sig_pk());
+    }
+}
+
+#[test]
+fn messages_do_not_carry_client_index() {
+    let config = classic::config::Config::new(4);
+    let (mut server, mut clients) = registration(&config, &mut OsRng).unwrap();
+
+    // The same client twice gets two unrelated handles
+    let client = &mut clients[3];
+    let m1 = round_1(client, &config, &mut OsRng).unwrap();
+    let first = client.send_m1(m1, &mut server);
+    let m1 = round_1(client, &config, &mut OsRng).unwrap();
+    let second = client.send_m1(m1, &mut server);
+
+    assert_ne!(first, second);
+    assert_ne!(first, 3);
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use generic_anon_ake::{
+    classic,
+    common::{error::AkeError, protocol::registration, schedule::TRAFFIC_KEY_LENGTH, suite::Suite},
+    pq::{
+        self,
+        supported_algs::{get_kem_algorithm, get_signature_algorithm},
+    },
+};
+use rand::rngs::OsRng;
+
+use common::handshake;
+
+fn both_sides_share_the_schedule<S: Suite>(config: &S) {
+    let (client, server, handle) = handshake(config);
+    let client_schedule = client.get_key_schedule().unwrap();
+    let server_schedule = server.get_key_schedule(handle).unwrap();
+
+    assert_eq!(
+        client_schedule.get_client_key(),
+        server_schedule.get_client_key()
+    );
+    assert_eq!(
+        client_schedule.get_server_key(),
+        server_schedule.get_server_key()
+    );
+    assert_eq!(
+        client_schedule.get_client_confirmation_key(),
+        server_schedule.get_client_confirmation_key()
+    );
+    assert_eq!(
+        client_schedule.get_server_confirmation_key(),
+        server_schedule.get_server_confirmation_key()
+    );
+    assert_eq!(
+        client_schedule.get_resumption_secret(),
+        server_schedule.get_resumption_secret()
+    );
+
+    assert_eq!(client_schedule.get_client_key().len(), TRAFFIC_KEY_LENGTH);
+    assert_ne!(
+        client_schedule.get_client_key(),
+        client_schedule.get_server_key()
+    );
+    assert_ne!(
+        client_schedule.get_client_confirmation_key(),
+        client_schedule.get_server_confirmation_key()
+    );
+    assert_ne!(client_schedule.get_client_key(), client.get_key().unwrap());
+}
+
+#[test]
+fn classic_sides_share_the_key_schedule() {
+    both_sides_share_the_schedule(&classic::config::Config::new(3));
+}
+
+#[test]
+fn pq_sides_share_the_key_schedule() {
+    let kemalg = get_kem_algorithm("Kyber512").unwrap();
+    let sigalg = get_signature_algorithm("Dilithium2").unwrap();
+    both_sides_share_the_schedule(&pq::config::Config::new(3, kemalg, sigalg));
+}
+
+#[test]
+fn exported_keying_material_depends_on_label_context_and_session() {
+    let config = classic::config::Config::new(2);
+    let (client, server, handle) = handshake(&config);
+    let export = |label: &[u8], context: &[u8], length| {
+        client
+            .export_keying_material(&config, label, context, length)
+            .unwrap()
+    };
+
+    let exported = export(b"application", b"context", 48);
+    assert_eq!(exported.len(), 48);
+    assert_eq!(
+        exported,
+        server
+            .export_keying_material(&config, handle, b"application", b"context", 48)
+            .unwrap()
+    );
+    assert_ne!(exported, export(b"application", b"other context", 48));
+    assert_ne!(exported, export(b"other application", b"context", 48));
+    // Labels and contexts are length-prefixed, so moving bytes between them matters
+    assert_ne!(exported, export(b"applicationcontext", b"", 48));
+    assert_eq!(exported[..16], export(b"application", b"context", 16));
+
+    let (other, _, _) = handshake(&config);
+    assert_ne!(
+        exported,
+        other
+            .export_keying_material(&config, b"application", b"context", 48)
+            .unwrap()
+    );
+}
+
+#[test]
+fn exporter_needs_a_completed_session() {
+    let config = classic::config::Config::new(2);
+    let (_, clients) = registration(&config, &mut OsRng).unwrap();
+
+    assert_eq!(
+        clients[0].export_keying_material(&config, b"application", b"", 32),
+        Err(AkeError::MissingState("key schedule"))
+    );
+    // HKDF-Expand outputs at most 255 hash lengths
+    let (client, _, _) = handshake(&config);
+    assert_eq!(
+        client.export_keying_material(&config, b"application", b"", 255 * 32 + 1),
+        Err(AkeError::Primitive("HKDF"))
+    );
+}
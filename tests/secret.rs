@@ -0,0 +1,56 @@
+use generic_anon_ake::{
+    classic,
+    common::{
+        protocol::{registration, round_1, round_2, round_3, round_4, round_5, round_6},
+        secret::{Secret, Wipe},
+    },
+};
+use rand::rngs::OsRng;
+
+#[test]
+fn secrets_are_redacted() {
+    let secret = Secret::new(vec![0xAA; 32]);
+
+    assert_eq!(format!("{secret:?}"), "<redacted>");
+    assert_eq!(secret.expose(), &vec![0xAA; 32]);
+}
+
+#[test]
+fn wipe_clears_bytes() {
+    let mut open = (vec![1_u8; 32], vec![2_u8; 32]);
+    open.wipe();
+
+    assert!(open.0.is_empty());
+    assert!(open.1.is_empty());
+}
+
+#[test]
+fn debug_of_client_and_server_hides_session_secrets() {
+    let config = classic::config::Config::new(2);
+    let (mut server, mut clients) = registration(&config, &mut OsRng).unwrap();
+    let mut client = clients.remove(0);
+
+    let m1 = round_1(&mut client, &config, &mut OsRng).unwrap();
+    let handle = client.send_m1(m1, &mut server);
+    let m2 = round_2(&mut server, &config, handle, &mut OsRng).unwrap();
+    server.send_m2(m2, &mut client);
+    let m3 = round_3(&mut client, &config, &mut OsRng, false).unwrap();
+    client.send_m3(m3, &mut server);
+    let m4 = round_4(&mut server, &config, handle).unwrap();
+    server.send_m4(m4, &mut client);
+    let m5 = round_5(&mut client, &config, &mut OsRng, false).unwrap();
+    client.send_m5(m5, &mut server);
+    round_6(&mut server, &config, handle, false).unwrap();
+
+    let client_debug = format!("{client:?}");
+    let server_debug = format!("{server:?}");
+    for secret in [
+        client.get_key().unwrap(),
+        client.get_sid().unwrap(),
+        client.get_ni(),
+    ] {
+        let printed = format!("{secret:?}");
+        assert!(!client_debug.contains(&printed));
+        assert!(!server_debug.contains(&printed));
+    }
+}
@@ -0,0 +1,224 @@
+mod common;
+
+use std::{io::Cursor, thread, time::Duration};
+
+use generic_anon_ake::{
+    classic,
+    common::{
+        error::AkeError,
+        net::{send_frame, serve_session},
+        protocol::{registration, round_1, round_2, round_3, round_4, round_5, round_6},
+        suite::Suite,
+        wire::encode_m1,
+    },
+    pq::{
+        self,
+        supported_algs::{get_kem_algorithm, get_signature_algorithm},
+    },
+};
+use rand::rngs::OsRng;
+
+use common::run_rounds;
+
+// Runs one handshake per client against a single server, every round being
+// played for all sessions in a different order before the next round starts
+fn interleaved_handshakes<S: Suite>(config: &S) {
+    let (mut server, mut clients) = registration(config, &mut OsRng).unwrap();
+    let n = clients.len();
+
+    let mut handles = Vec::new();
+    for client in clients.iter_mut() {
+        let m1 = round_1(client, config, &mut OsRng).unwrap();
+        handles.push(client.send_m1(m1, &mut server));
+    }
+    assert_eq!(server.sessions_number(), n);
+
+    for i in (0..n).rev() {
+        let m2 = round_2(&mut server, config, handles[i], &mut OsRng).unwrap();
+        server.send_m2(m2, &mut clients[i]);
+    }
+
+    for i in (0..n).step_by(2).chain((1..n).step_by(2)) {
+        let m3 = round_3(&mut clients[i], config, &mut OsRng, false).unwrap();
+        clients[i].send_m3(m3, &mut server);
+    }
+
+    for i in (1..n).step_by(2).chain((0..n).step_by(2)) {
+        let m4 = round_4(&mut server, config, handles[i]).unwrap();
+        server.send_m4(m4, &mut clients[i]);
+    }
+
+    for i in (0..n).rev() {
+        let m5 = round_5(&mut clients[i], config, &mut OsRng, false).unwrap();
+        clients[i].send_m5(m5, &mut server);
+    }
+
+    for &handle in handles.iter().skip(1).chain(handles.first()) {
+        round_6(&mut server, config, handle, false).unwrap();
+    }
+
+    for (client, &handle) in clients.iter().zip(handles.iter()) {
+        assert_eq!(client.get_key().unwrap(), server.get_key(handle).unwrap());
+        assert_eq!(client.get_sid().unwrap(), server.get_sid(handle).unwrap());
+    }
+    assert_ne!(clients[0].get_key().unwrap(), clients[1].get_key().unwrap());
+}
+
+#[test]
+fn interleaved_handshakes_pq() {
+    let kemalg = get_kem_algorithm("Kyber768").unwrap();
+    let sigalg = get_signature_algorithm("Dilithium3").unwrap();
+    let config = pq::config::Config::new(4, kemalg, sigalg);
+
+    interleaved_handshakes(&config);
+}
+
+#[test]
+fn interleaved_handshakes_classic() {
+    let config = classic::config::Config::new(5);
+
+    interleaved_handshakes(&config);
+}
+
+#[test]
+fn sessions_keep_their_own_r() {
+    let config = classic::config::Config::new(2);
+    let (mut server, mut clients) = registration(&config, &mut OsRng).unwrap();
+
+    let m1 = round_1(&mut clients[0], &config, &mut OsRng).unwrap();
+    let first = clients[0].send_m1(m1, &mut server);
+    let m1 = round_1(&mut clients[1], &config, &mut OsRng).unwrap();
+    let second = clients[1].send_m1(m1, &mut server);
+
+    let m2 = round_2(&mut server, &config, first, &mut OsRng).unwrap();
+    server.send_m2(m2, &mut clients[0]);
+    let m2 = round_2(&mut server, &config, second, &mut OsRng).unwrap();
+    server.send_m2(m2, &mut clients[1]);
+
+    // The second round 2 must not overwrite the r signed for the first session
+    assert_ne!(server.get_r(first).unwrap(), server.get_r(second).unwrap());
+
+    // r is signed only once comm_S has been received
+    assert_eq!(
+        round_4(&mut server, &config, first),
+        Err(AkeError::MissingState("m3 commitment comm_S"))
+    );
+
+    let m3 = round_3(&mut clients[0], &config, &mut OsRng, false).unwrap();
+    clients[0].send_m3(m3, &mut server);
+    let (r, _) = round_4(&mut server, &config, first).unwrap();
+
+    assert_eq!(r, server.get_r(first).unwrap());
+}
+
+type ClassicConfig = classic::config::Config;
+
+#[test]
+fn round_6_wipes_the_handshake_state() {
+    let config = ClassicConfig::new(2);
+    let (client, mut server, handle) = run_rounds(&config, 5).unwrap();
+    round_6(&mut server, &config, handle, false).unwrap();
+
+    assert_eq!(
+        server.get_r(handle),
+        Err(AkeError::MissingState("m2 randomness r"))
+    );
+    assert_eq!(
+        server.get_ns(handle),
+        Err(AkeError::MissingState("server nonce n_S"))
+    );
+    assert!(server.get_ccapke_keypair(handle).is_err());
+    assert!(server.get_open_server(handle).is_err());
+    assert_eq!(client.get_key().unwrap(), server.get_key(handle).unwrap());
+
+    server.finish_session(handle).unwrap();
+    assert!(!server.has_session(handle));
+    assert_eq!(server.sessions_number(), 0);
+    assert_eq!(
+        server.finish_session(handle),
+        Err(AkeError::MissingState("session for this handle"))
+    );
+}
+
+#[test]
+fn only_sessions_with_a_key_can_be_finished() {
+    let config = ClassicConfig::new(2);
+    let (mut server, mut clients) = registration(&config, &mut OsRng).unwrap();
+
+    let m1 = round_1(&mut clients[0], &config, &mut OsRng).unwrap();
+    let handle = clients[0].send_m1(m1, &mut server);
+
+    assert_eq!(
+        server.finish_session(handle),
+        Err(AkeError::MissingState("session key"))
+    );
+    assert!(server.has_session(handle));
+    assert!(server.abort_session(handle));
+    assert!(!server.abort_session(handle));
+}
+
+#[test]
+fn failed_round_6_drops_the_session() {
+    let config = ClassicConfig::new(2);
+    let (_, mut server, handle) = run_rounds(&config, 5).unwrap();
+    let ctxi = server.get_ctxi(handle).unwrap();
+    let open_s = server.get_open_server(handle).unwrap().clone();
+    server.receive_m5((ctxi[..ctxi.len() / 2].to_vec(), open_s, handle));
+
+    assert!(round_6(&mut server, &config, handle, false).is_err());
+    assert!(!server.has_session(handle));
+}
+
+#[test]
+fn open_sessions_are_capped() {
+    let config = ClassicConfig::new(3);
+    let (mut server, mut clients) = registration(&config, &mut OsRng).unwrap();
+    server.set_session_limits(2, Duration::from_secs(60));
+
+    let mut handles = Vec::new();
+    for client in clients.iter_mut() {
+        let m1 = round_1(client, &config, &mut OsRng).unwrap();
+        handles.push(client.send_m1(m1, &mut server));
+    }
+
+    // The oldest session made room for the last one
+    assert_eq!(server.sessions_number(), 2);
+    assert!(!server.has_session(handles[0]));
+    assert!(server.has_session(handles[1]));
+    assert!(server.has_session(handles[2]));
+}
+
+#[test]
+fn open_sessions_expire() {
+    let config = ClassicConfig::new(2);
+    let (mut server, mut clients) = registration(&config, &mut OsRng).unwrap();
+    server.set_session_limits(16, Duration::from_millis(50));
+
+    let m1 = round_1(&mut clients[0], &config, &mut OsRng).unwrap();
+    let first = clients[0].send_m1(m1, &mut server);
+    thread::sleep(Duration::from_millis(100));
+    let m1 = round_1(&mut clients[1], &config, &mut OsRng).unwrap();
+    let second = clients[1].send_m1(m1, &mut server);
+
+    assert!(!server.has_session(first));
+    assert!(server.has_session(second));
+
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(server.expire_sessions(), 1);
+    assert_eq!(server.sessions_number(), 0);
+}
+
+// The peer sends m1 and then goes away: the session it opened is not kept
+#[test]
+fn serve_session_aborts_a_broken_handshake() {
+    let config = ClassicConfig::new(2);
+    let (mut server, mut clients) = registration(&config, &mut OsRng).unwrap();
+
+    let m1 = round_1(&mut clients[0], &config, &mut OsRng).unwrap();
+    let mut stream = Cursor::new(Vec::new());
+    send_frame(&mut stream, &encode_m1(&config, &m1)).unwrap();
+    stream.set_position(0);
+
+    assert!(serve_session(&mut server, &config, &mut stream, &mut OsRng, false, false).is_err());
+    assert_eq!(server.sessions_number(), 0);
+}
@@ -0,0 +1,175 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use generic_anon_ake::{
+    classic,
+    common::{
+        error::AkeError,
+        store::KEY_FILE_VERSION,
+        suite::{Pke, Sig, Suite},
+    },
+    pq::{
+        self,
+        supported_algs::{get_kem_algorithm, get_signature_algorithm},
+    },
+};
+
+fn state_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!(
+        "generic-anon-ake-store-{name}-{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn pq_config(kem: &str) -> pq::config::Config {
+    let kemalg = get_kem_algorithm(kem).unwrap();
+    let sigalg = get_signature_algorithm("Dilithium2").unwrap();
+    pq::config::Config::new(2, kemalg, sigalg)
+}
+
+#[test]
+fn server_identity_survives_restarts_pq() {
+    let config = pq_config("Kyber512");
+    let dir = state_dir("server-pq");
+
+    let server = pq::store::server_keygen(&config, &dir).unwrap();
+    let pk_s = config.sig().public_key_to_bytes(&server.get_sig_pk());
+
+    // A restarted server and the clients see the same pk_S
+    let restarted = pq::store::load_server_keys(&config, &dir).unwrap();
+    assert_eq!(
+        config.sig().public_key_to_bytes(&restarted.get_sig_pk()),
+        pk_s
+    );
+    let pinned = pq::store::load_server_public_key(&config, &dir).unwrap();
+    assert_eq!(config.sig().public_key_to_bytes(&pinned), pk_s);
+
+    // The key is never replaced by a fresh one
+    assert!(matches!(
+        pq::store::server_keygen(&config, &dir),
+        Err(AkeError::Io(_))
+    ));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn server_identity_survives_restarts_classic() {
+    let config = classic::config::Config::new(2);
+    let dir = state_dir("server-classic");
+
+    let server = classic::store::server_keygen(&config, &dir).unwrap();
+    let restarted = classic::store::load_server_keys(&config, &dir).unwrap();
+
+    assert_eq!(server.get_sig_pk(), restarted.get_sig_pk());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn membership_survives_restarts() {
+    let config = classic::config::Config::new(2);
+    let dir = state_dir("membership");
+    fs::create_dir_all(&dir).unwrap();
+
+    let mut vks = Vec::new();
+    for i in 0..2 {
+        let key = dir.join(format!("client-{i}.key"));
+        let vk = classic::store::client_keygen_to_file(&config, &key).unwrap();
+        classic::store::register_client(&config, &dir, &vk).unwrap();
+        vks.push(Pke::public_key_to_bytes(config.pke(), &vk));
+
+        let (loaded, _) = classic::store::load_client_keys(&config, &key).unwrap();
+        assert_eq!(Pke::public_key_to_bytes(config.pke(), &loaded), vks[i]);
+    }
+
+    let server = classic::store::load_server(&config, &dir).unwrap();
+    let registered: Vec<Vec<u8>> = server
+        .get_clients_keys()
+        .iter()
+        .map(|vk| Pke::public_key_to_bytes(config.pke(), vk))
+        .collect();
+    assert_eq!(registered, vks);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn key_files_record_version_suite_and_algorithm() {
+    let dir = state_dir("header");
+    fs::create_dir_all(&dir).unwrap();
+    let key = dir.join("client.key");
+
+    let kyber512 = pq_config("Kyber512");
+    pq::store::client_keygen_to_file(&kyber512, &key).unwrap();
+    let bytes = fs::read(&key).unwrap();
+    assert_eq!(bytes[0], KEY_FILE_VERSION);
+    assert_eq!(bytes[1], kyber512.suite_id());
+    let name = kyber512.pke().algorithm_name();
+    assert_eq!(&bytes[7..7 + name.len()], name.as_bytes());
+
+    // Same suite, other KEM
+    let kyber768 = pq_config("Kyber768");
+    assert_eq!(
+        pq::store::load_client_keys(&kyber768, &key).unwrap_err(),
+        AkeError::Decoding("key file: algorithm")
+    );
+
+    // Other suite
+    let classic = classic::config::Config::new(2);
+    assert_eq!(
+        classic::store::load_client_keys(&classic, &key).unwrap_err(),
+        AkeError::Decoding("key file: suite identifier")
+    );
+
+    // A public key file is not a key pair
+    assert_eq!(
+        pq::store::load_client_keys(&kyber512, &pq::store::public_key_path(&key)).unwrap_err(),
+        AkeError::Decoding("key file: file type")
+    );
+
+    let mut other_version = bytes.clone();
+    other_version[0] = KEY_FILE_VERSION + 1;
+    fs::write(&key, other_version).unwrap();
+    assert_eq!(
+        pq::store::load_client_keys(&kyber512, &key).unwrap_err(),
+        AkeError::Decoding("key file: version")
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn secret_key_files_are_private_and_never_overwritten() {
+    let config = classic::config::Config::new(2);
+    let dir = state_dir("permissions");
+    fs::create_dir_all(&dir).unwrap();
+    let key = dir.join("client.key");
+
+    classic::store::server_keygen(&config, &dir).unwrap();
+    classic::store::client_keygen_to_file(&config, &key).unwrap();
+    assert_eq!(
+        classic::store::client_keygen_to_file(&config, &key).unwrap_err(),
+        AkeError::Io(io::ErrorKind::AlreadyExists)
+    );
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&key), 0o600);
+        assert_eq!(mode(&dir.join("server.key")), 0o600);
+        // Public files keep the default permissions
+        let default = dir.join("default");
+        fs::write(&default, b"").unwrap();
+        assert_eq!(mode(&classic::store::public_key_path(&key)), mode(&default));
+        assert_eq!(mode(&dir.join("server.pub")), mode(&default));
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use generic_anon_ake::{
+    common::error::AkeError,
+    pq::{
+        self,
+        supported_algs::{
+            get_kem_algorithm, get_signature_algorithm, SUPPORTED_KEMS, SUPPORTED_SIGS,
+        },
+    },
+};
+
+use common::handshake;
+
+#[test]
+fn every_supported_name_resolves() {
+    for name in SUPPORTED_KEMS {
+        assert!(get_kem_algorithm(name).is_ok(), "{name}");
+    }
+    for name in SUPPORTED_SIGS {
+        assert!(get_signature_algorithm(name).is_ok(), "{name}");
+    }
+
+    assert_eq!(
+        get_kem_algorithm("ML-KEM-256").err(),
+        Some(AkeError::Decoding("KEM name"))
+    );
+    assert_eq!(
+        get_signature_algorithm("ML-DSA-2").err(),
+        Some(AkeError::Decoding("signature name"))
+    );
+}
+
+#[test]
+fn fips_names_are_distinct_from_round_3_names() {
+    for (fips, round_3) in [
+        ("ML-KEM-512", "Kyber512"),
+        ("ML-KEM-768", "Kyber768"),
+        ("ML-KEM-1024", "Kyber1024"),
+    ] {
+        let fips = get_kem_algorithm(fips).unwrap();
+        let round_3 = get_kem_algorithm(round_3).unwrap();
+        assert_ne!(fips.algorithm(), round_3.algorithm());
+    }
+
+    for (fips, round_3) in [
+        ("ML-DSA-44", "Dilithium2"),
+        ("ML-DSA-65", "Dilithium3"),
+        ("ML-DSA-87", "Dilithium5"),
+    ] {
+        let fips = get_signature_algorithm(fips).unwrap();
+        let round_3 = get_signature_algorithm(round_3).unwrap();
+        assert_ne!(fips.algorithm(), round_3.algorithm());
+    }
+}
+
+// liboqs 0.8 renamed the SPHINCS+ SHA-256 variants to SHA2
+#[test]
+fn sphincs_names_select_the_liboqs_schemes() {
+    for (name, liboqs) in [
+        ("SphincsSha2128fSimple", "SPHINCS+-SHA2-128f-simple"),
+        ("SphincsSha2128sSimple", "SPHINCS+-SHA2-128s-simple"),
+        ("SphincsShake128fSimple", "SPHINCS+-SHAKE-128f-simple"),
+        ("SphincsShake128sSimple", "SPHINCS+-SHAKE-128s-simple"),
+    ] {
+        let sigalg = get_signature_algorithm(name).unwrap();
+        assert_eq!(sigalg.algorithm().name(), liboqs);
+    }
+}
+
+fn run_handshake(kem: &str, sig: &str) {
+    let kemalg = get_kem_algorithm(kem).unwrap();
+    let sigalg = get_signature_algorithm(sig).unwrap();
+    let config = pq::config::Config::new(2, kemalg, sigalg);
+
+    let (client, server, handle) = handshake(&config);
+
+    assert_eq!(client.get_key().unwrap(), server.get_key(handle).unwrap());
+    assert_eq!(client.get_sid().unwrap(), server.get_sid(handle).unwrap());
+}
+
+#[test]
+fn protocol_works_with_ml_kem_and_ml_dsa() {
+    run_handshake("ML-KEM-768", "ML-DSA-65");
+}
+
+#[test]
+fn protocol_works_with_hqc_bike_and_frodokem() {
+    for kem in ["Hqc128", "BikeL1", "FrodoKem640Aes", "FrodoKem976Shake"] {
+        run_handshake(kem, "ML-DSA-44");
+    }
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use generic_anon_ake::{classic, common::suite::Suite};
+use rand::rngs::OsRng;
+
+use common::run_rounds_with;
+
+// Flips one bit of a wire message, keeping the header so it still decodes
+// as the same message whenever the payload allows it
+fn tamper(bytes: &mut [u8], position: usize) {
+    let position = 3 + position % (bytes.len() - 3);
+    bytes[position] ^= 0x01;
+}
+
+// Runs the handshake, tampering with message `target` (1 to 5) if given.
+// Returns the client and server session keys and sids, or None on abort.
+#[allow(clippy::type_complexity)]
+fn handshake<S: Suite>(
+    config: &S,
+    target: Option<(usize, usize)>,
+) -> Option<((Vec<u8>, Vec<u8>), (Vec<u8>, Vec<u8>))> {
+    let relay = |index: usize, mut bytes: Vec<u8>| {
+        if let Some((_, position)) = target.filter(|(i, _)| *i == index) {
+            tamper(&mut bytes, position);
+        }
+        bytes
+    };
+    let (client, server, handle) = run_rounds_with(config, &mut OsRng, 6, relay).ok()?;
+
+    Some((
+        (
+            client.get_key().unwrap().to_vec(),
+            client.get_sid().unwrap().to_vec(),
+        ),
+        (
+            server.get_key(handle).unwrap().to_vec(),
+            server.get_sid(handle).unwrap().to_vec(),
+        ),
+    ))
+}
+
+#[test]
+fn both_sides_derive_the_same_key_and_sid() {
+    let config = classic::config::Config::new(3);
+
+    let (client, server) = handshake(&config, None).unwrap();
+
+    assert_eq!(client, server);
+    assert_ne!(client.0, client.1);
+}
+
+#[test]
+fn tampering_with_any_message_aborts_or_splits_the_keys() {
+    let config = classic::config::Config::new(2);
+
+    for index in 1..=5 {
+        for position in [0, 1, 17, 40, 97, 1000] {
+            if let Some((client, server)) = handshake(&config, Some((index, position))) {
+                assert_ne!(client.0, server.0, "m{index} at {position}");
+                assert_ne!(client.1, server.1, "m{index} at {position}");
+            }
+        }
+    }
+}
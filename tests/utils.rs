@@ -1,4 +1,4 @@
-use generic_anon_ake::common::utils::{get_nonce, get_random_key32, to_hex, xor};
+use generic_anon_ake::common::utils::{ct_eq, get_nonce, get_random_key32, to_hex, xor};
 
 #[test]
 fn get_random_key32_works() {
@@ -40,3 +40,15 @@ fn to_hex_works() {
     let res: String = to_hex(&x);
     assert_eq!(res, "517bff003048");
 }
+
+#[test]
+fn ct_eq_checks_lengths() {
+    let x: Vec<u8> = vec![81, 123, 255, 0, 48, 72];
+
+    assert!(ct_eq(&x, &x.clone()));
+    assert!(ct_eq(&[], &[]));
+    assert!(!ct_eq(&x, &x[..5]));
+    assert!(!ct_eq(&x[..5], &x));
+    assert!(!ct_eq(&x, &[]));
+    assert!(!ct_eq(&x, &[81, 123, 255, 0, 48, 73]));
+}
@@ -0,0 +1,164 @@
+use std::{env, fs, path::Path};
+
+use generic_anon_ake::{
+    classic,
+    common::{
+        error::{AkeError, Result},
+        prf::PrfAlgorithm,
+        vectors::{check_vector, generate_vector, read_vectors, write_vectors, TestVector},
+    },
+    hybrid,
+    pq::{
+        self,
+        supported_algs::{get_kem_algorithm, get_signature_algorithm},
+    },
+};
+
+fn classic_config(users: u32, prf: &str) -> classic::config::Config {
+    let mut config = classic::config::Config::new(users);
+    config.set_prf_algorithm(PrfAlgorithm::from_name(prf).unwrap());
+    config
+}
+
+#[test]
+fn classic_vector_is_reproducible() {
+    let config = classic_config(3, "AES-CTR");
+
+    let vector = generate_vector(&config, 1, 2).unwrap();
+
+    assert_eq!(vector.suite, "ECIES-secp256k1+ECDSA-secp256k1");
+    assert_eq!(vector, generate_vector(&config, 1, 2).unwrap());
+    assert_ne!(vector, generate_vector(&config, 2, 2).unwrap());
+    assert!(check_vector(&config, &vector).is_ok());
+}
+
+#[test]
+fn vector_files_round_trip() {
+    let config = classic_config(2, "KMAC256");
+    let path = env::temp_dir().join(format!(
+        "generic-anon-ake-vectors-{}.json",
+        std::process::id()
+    ));
+    let vectors = vec![
+        generate_vector(&config, 0, 0).unwrap(),
+        generate_vector(&config, 1, 1).unwrap(),
+    ];
+
+    write_vectors(&path, &vectors).unwrap();
+    let read = read_vectors(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(read, vectors);
+}
+
+#[test]
+fn check_reports_the_first_mismatch() {
+    let config = classic_config(2, "AES-CTR");
+    let vector = generate_vector(&config, 5, 0).unwrap();
+
+    let mut wrong = vector.clone();
+    wrong.m3 = vector.m4.clone();
+    assert_eq!(
+        check_vector(&config, &wrong),
+        Err(AkeError::Verification("test vector m3"))
+    );
+
+    let mut wrong = vector.clone();
+    wrong.sid = vector.k.clone();
+    assert_eq!(
+        check_vector(&config, &wrong),
+        Err(AkeError::Verification("test vector sid"))
+    );
+
+    assert_eq!(
+        check_vector(&classic_config(2, "SHAKE256"), &vector),
+        Err(AkeError::Decoding("test vector: PRF"))
+    );
+    assert_eq!(
+        check_vector(&classic_config(3, "AES-CTR"), &vector),
+        Err(AkeError::Decoding("test vector: number of clients"))
+    );
+}
+
+// Checks every vector of a file shipped under vectors/
+fn check_shipped(file: &str, check: impl Fn(&TestVector) -> Result<()>) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(file);
+    let shipped =
+        read_vectors(&path).unwrap_or_else(|e| panic!("{} cannot be read: {e}", path.display()));
+
+    assert!(!shipped.is_empty());
+    for vector in shipped.iter() {
+        assert_eq!(check(vector), Ok(()), "seed {}", vector.seed);
+    }
+}
+
+// Written with `generic-anon-ake-classic -c 3 vectors --write`
+#[test]
+fn shipped_classic_vectors_match() {
+    check_shipped(classic::vectors::VECTORS_FILE, classic::vectors::check);
+}
+
+// Written with `generic-anon-ake -k Kyber768 -s Dilithium3 -c 2 vectors
+// --write`. They pin liboqs 0.13: another version may sample differently.
+#[test]
+fn shipped_pq_vectors_match() {
+    check_shipped(pq::vectors::VECTORS_FILE, pq::vectors::check);
+}
+
+// Written with `generic-anon-ake-hybrid -k Kyber768 -s Dilithium3 -c 2 vectors
+// --write`
+#[test]
+fn shipped_hybrid_vectors_match() {
+    check_shipped(hybrid::vectors::VECTORS_FILE, hybrid::vectors::check);
+}
+
+// liboqs draws its randomness from the seeded generator, so a seed fixes the
+// whole PQ handshake too
+#[test]
+fn pq_vector_is_reproducible() {
+    let kemalg = get_kem_algorithm("Kyber768").unwrap();
+    let sigalg = get_signature_algorithm("Dilithium3").unwrap();
+    let config = pq::config::Config::new(2, kemalg, sigalg);
+
+    let vector = generate_vector(&config, 0, 0).unwrap();
+
+    assert_eq!(vector.suite, "Kyber768+Dilithium3");
+    assert_eq!(vector, generate_vector(&config, 0, 0).unwrap());
+    assert_eq!(check_vector(&config, &vector), Ok(()));
+}
+
+// The suite name alone gives back the configuration, also when the PKE and
+// CCA-PKE differ and when the signature name holds a '+' of its own
+#[test]
+fn pq_vector_config_is_read_from_the_suite() {
+    let kemalg = get_kem_algorithm("Kyber512").unwrap();
+    let sigalg = get_signature_algorithm("SphincsShake128fSimple").unwrap();
+    let mut config = pq::config::Config::new(2, kemalg, sigalg);
+    config.set_ccapke_algorithm(get_kem_algorithm("ML-KEM-512").unwrap());
+
+    let vector = generate_vector(&config, 3, 1).unwrap();
+
+    assert_eq!(
+        vector.suite,
+        "Kyber512/ML-KEM-512+SPHINCS+-SHAKE-128f-simple"
+    );
+    assert_eq!(pq::vectors::check(&vector), Ok(()));
+    assert!(hybrid::vectors::check(&vector).is_err());
+    assert!(classic::vectors::check(&vector).is_err());
+}
+
+#[test]
+fn hybrid_vector_config_is_read_from_the_suite() {
+    let kemalg = get_kem_algorithm("ML-KEM-768").unwrap();
+    let sigalg = get_signature_algorithm("ML-DSA-44").unwrap();
+    let config = hybrid::config::Config::new(2, kemalg, sigalg);
+
+    let vector = generate_vector(&config, 0, 0).unwrap();
+
+    assert_eq!(
+        vector.suite,
+        "ECIES-secp256k1+ML-KEM-768+ECDSA-secp256k1+ML-DSA-44"
+    );
+    assert_eq!(hybrid::vectors::check(&vector), Ok(()));
+    assert!(pq::vectors::check(&vector).is_err());
+}
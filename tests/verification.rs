@@ -0,0 +1,119 @@
+mod common;
+
+use generic_anon_ake::{
+    classic::config::Config,
+    common::{
+        error::AkeError,
+        protocol::{round_2, round_3, round_4, round_5, round_6},
+        suite::{CcaPke, Pke, Sig, Suite},
+        utils::get_random_key32,
+    },
+};
+use rand::rngs::OsRng;
+
+use common::run_rounds;
+
+#[test]
+fn round_3_rejects_bad_signature2() {
+    let config = Config::new(2);
+    let (mut client, mut server, handle) = run_rounds(&config, 1).unwrap();
+
+    let ((cis, mut r, pk), signature2, handle) =
+        round_2(&mut server, &config, handle, &mut OsRng).unwrap();
+    r[0] ^= 1;
+    server.send_m2(((cis, r, pk), signature2, handle), &mut client);
+
+    let res = round_3(&mut client, &config, &mut OsRng, false);
+
+    assert_eq!(res, Err(AkeError::BadSignature2));
+}
+
+#[test]
+fn round_5_rejects_inconsistent_ciphertext() {
+    let config = Config::new(3);
+    let (mut client, mut server, handle) = run_rounds(&config, 1).unwrap();
+
+    // A server trying to tell clients apart encrypts a different n_S for j = 2
+    let ((mut cis, r, pk), _, handle) = round_2(&mut server, &config, handle, &mut OsRng).unwrap();
+    let vk2 = server.get_clients_keys()[2];
+    let r2 = config.pke_coins(&r, 2).unwrap();
+    cis[2] = config
+        .pke()
+        .pke_enc(&vk2, &get_random_key32(), &r2)
+        .unwrap();
+    let to_sign = [
+        cis.concat(),
+        r.clone(),
+        CcaPke::public_key_to_bytes(config.ccapke(), &pk),
+    ]
+    .concat();
+    let (_, sk_s) = server.get_sig_keypair();
+    let signature2 = config.sig().sig_sign(sk_s, &to_sign).unwrap();
+    server.send_m2(((cis, r, pk), signature2, handle), &mut client);
+
+    let m3 = round_3(&mut client, &config, &mut OsRng, false).unwrap();
+    client.send_m3(m3, &mut server);
+    let m4 = round_4(&mut server, &config, handle).unwrap();
+    server.send_m4(m4, &mut client);
+
+    let res = round_5(&mut client, &config, &mut OsRng, false);
+
+    assert_eq!(res.unwrap_err(), AkeError::InconsistentCiphertext { j: 2 });
+    assert_eq!(client.get_key(), Err(AkeError::MissingState("session key")));
+    assert_eq!(
+        client.get_sid(),
+        Err(AkeError::MissingState("session identifier"))
+    );
+}
+
+#[test]
+fn round_5_rejects_bad_signature4() {
+    let config = Config::new(2);
+    let (mut client, server, handle) = run_rounds(&config, 4).unwrap();
+
+    let (_, sk_s) = server.get_sig_keypair();
+    let signature4 = config.sig().sig_sign(sk_s, b"not r").unwrap();
+    server.send_m4((server.get_r(handle).unwrap(), signature4), &mut client);
+
+    let res = round_5(&mut client, &config, &mut OsRng, false);
+
+    assert_eq!(res.unwrap_err(), AkeError::BadSignature4);
+    assert_eq!(client.get_key(), Err(AkeError::MissingState("session key")));
+}
+
+#[test]
+fn round_6_rejects_bad_commitment() {
+    let config = Config::new(2);
+    let (mut client, mut server, handle) = run_rounds(&config, 4).unwrap();
+
+    let (ctxi, (x, mut r), _) = round_5(&mut client, &config, &mut OsRng, false).unwrap();
+    r[0] ^= 1;
+    client.send_m5((ctxi, (x, r), handle), &mut server);
+
+    let res = round_6(&mut server, &config, handle, false);
+
+    assert_eq!(res, Err(AkeError::BadCommitment));
+    assert!(server.get_key(handle).is_err());
+    assert!(server.get_sid(handle).is_err());
+}
+
+#[test]
+fn round_6_rejects_empty_commitment() {
+    let config = Config::new(2);
+    let (mut client, mut server, handle) = run_rounds(&config, 1).unwrap();
+
+    let m2 = round_2(&mut server, &config, handle, &mut OsRng).unwrap();
+    server.send_m2(m2, &mut client);
+    let (_, handle) = round_3(&mut client, &config, &mut OsRng, false).unwrap();
+    // An empty comm_S is a prefix of every commitment
+    client.send_m3((Vec::new(), handle), &mut server);
+    let m4 = round_4(&mut server, &config, handle).unwrap();
+    server.send_m4(m4, &mut client);
+    let m5 = round_5(&mut client, &config, &mut OsRng, false).unwrap();
+    client.send_m5(m5, &mut server);
+
+    let res = round_6(&mut server, &config, handle, false);
+
+    assert_eq!(res, Err(AkeError::BadCommitment));
+    assert!(server.get_key(handle).is_err());
+}
@@ -0,0 +1,193 @@
+use generic_anon_ake::{
+    classic,
+    common::{
+        error::AkeError,
+        protocol::{registration, round_1, round_2, round_3, round_4, round_5},
+        suite::{CcaPke, Sig, Suite},
+        wire::{
+            decode_m1, decode_m2, decode_m3, decode_m4, decode_m5, encode_m1, encode_m2, encode_m3,
+            encode_m4, encode_m5, peek_header, MessageType, PROTOCOL_VERSION,
+        },
+    },
+    pq::{
+        self,
+        supported_algs::{get_kem_algorithm, get_signature_algorithm},
+    },
+};
+use rand::rngs::OsRng;
+
+// Runs the protocol in memory and checks that every message survives an
+// encode/decode round trip and that the decoded message is the one used
+fn wire_roundtrip<S: Suite>(config: &S) {
+    let (mut server, mut clients) = registration(config, &mut OsRng).unwrap();
+    let mut client = clients.remove(0);
+
+    let m1 = round_1(&mut client, config, &mut OsRng).unwrap();
+    let bytes = encode_m1(config, &m1);
+    assert_eq!(
+        peek_header(&bytes).unwrap(),
+        (PROTOCOL_VERSION, config.suite_id(), MessageType::M1)
+    );
+    let m1_decoded = decode_m1(config, &bytes).unwrap();
+    assert_eq!(m1, m1_decoded);
+    let handle = client.send_m1(m1_decoded, &mut server);
+
+    let m2 = round_2(&mut server, config, handle, &mut OsRng).unwrap();
+    let bytes = encode_m2(config, &m2);
+    let m2_decoded = decode_m2(config, &bytes).unwrap();
+    assert_eq!(bytes, encode_m2(config, &m2_decoded));
+    assert_eq!(m2_decoded.2, handle);
+    server.send_m2(m2_decoded, &mut client);
+
+    let m3 = round_3(&mut client, config, &mut OsRng, false).unwrap();
+    let bytes = encode_m3(config, &m3);
+    let m3_decoded = decode_m3(config, &bytes).unwrap();
+    assert_eq!(m3, m3_decoded);
+    assert!(decode_m1(config, &bytes).is_err());
+    client.send_m3(m3_decoded, &mut server);
+
+    let m4 = round_4(&mut server, config, handle).unwrap();
+    let bytes = encode_m4(config, &m4);
+    let m4_decoded = decode_m4(config, &bytes).unwrap();
+    assert_eq!(bytes, encode_m4(config, &m4_decoded));
+    assert!(config
+        .sig()
+        .sig_vry(&client.get_pks().unwrap(), &m4_decoded.0, &m4_decoded.1));
+    server.send_m4(m4_decoded, &mut client);
+
+    let m5 = round_5(&mut client, config, &mut OsRng, false).unwrap();
+    let bytes = encode_m5(config, &m5);
+    let m5_decoded = decode_m5(config, &bytes).unwrap();
+    assert_eq!(bytes, encode_m5(config, &m5_decoded));
+    assert_eq!(
+        config.ccapke().ciphertext_to_bytes(&m5.0),
+        config.ccapke().ciphertext_to_bytes(&m5_decoded.0)
+    );
+}
+
+#[test]
+fn wire_roundtrip_pq() {
+    let kemalg = get_kem_algorithm("Kyber768").unwrap();
+    let sigalg = get_signature_algorithm("Dilithium3").unwrap();
+    let config = pq::config::Config::new(3, kemalg, sigalg);
+
+    wire_roundtrip(&config);
+}
+
+// Falcon signatures have a variable length and SPHINCS+ ones are the largest
+#[test]
+fn wire_roundtrip_pq_falcon_and_sphincs() {
+    for sig in [
+        "Falcon512",
+        "Falcon1024",
+        "SphincsSha2128fSimple",
+        "SphincsSha2128sSimple",
+        "SphincsShake128fSimple",
+        "SphincsShake128sSimple",
+    ] {
+        let kemalg = get_kem_algorithm("ML-KEM-512").unwrap();
+        let sigalg = get_signature_algorithm(sig).unwrap();
+        let config = pq::config::Config::new(2, kemalg, sigalg);
+
+        wire_roundtrip(&config);
+    }
+}
+
+#[test]
+fn wire_roundtrip_pq_with_separate_ccapke_kem() {
+    let kemalg = get_kem_algorithm("ClassicMcEliece348864f").unwrap();
+    let sigalg = get_signature_algorithm("Dilithium2").unwrap();
+    let mut config = pq::config::Config::new(3, kemalg, sigalg);
+    config.set_ccapke_algorithm(get_kem_algorithm("ML-KEM-512").unwrap());
+
+    wire_roundtrip(&config);
+}
+
+// pk* of m2 is a key of the CCA-PKE, so both sides must choose the same KEM
+#[test]
+fn wire_rejects_m2_with_another_ccapke_kem() {
+    let config = |ccapke_kem: &str| {
+        let kemalg = get_kem_algorithm("Kyber512").unwrap();
+        let sigalg = get_signature_algorithm("Dilithium2").unwrap();
+        let mut config = pq::config::Config::new(2, kemalg, sigalg);
+        config.set_ccapke_algorithm(get_kem_algorithm(ccapke_kem).unwrap());
+        config
+    };
+    let server_config = config("ML-KEM-1024");
+    let client_config = config("ML-KEM-512");
+    let (mut server, mut clients) = registration(&server_config, &mut OsRng).unwrap();
+    let mut client = clients.remove(0);
+
+    let m1 = round_1(&mut client, &server_config, &mut OsRng).unwrap();
+    let handle = client.send_m1(m1, &mut server);
+    let m2 = round_2(&mut server, &server_config, handle, &mut OsRng).unwrap();
+    let bytes = encode_m2(&server_config, &m2);
+
+    assert!(decode_m2(&server_config, &bytes).is_ok());
+    assert!(decode_m2(&client_config, &bytes).is_err());
+}
+
+#[test]
+fn wire_roundtrip_classic() {
+    let config = classic::config::Config::new(3);
+
+    wire_roundtrip(&config);
+}
+
+#[test]
+fn wire_rejects_malformed_messages() {
+    let config = classic::config::Config::new(2);
+    let m1 = vec![7u8; 32];
+    let bytes = encode_m1(&config, &m1);
+
+    let mut bad_version = bytes.clone();
+    bad_version[0] = PROTOCOL_VERSION + 1;
+    assert_eq!(
+        decode_m1(&config, &bad_version),
+        Err(AkeError::Decoding("protocol version"))
+    );
+
+    let mut bad_suite = bytes.clone();
+    bad_suite[1] = pq::config::SUITE_ID;
+    assert_eq!(
+        decode_m1(&config, &bad_suite),
+        Err(AkeError::Decoding("suite identifier"))
+    );
+
+    assert_eq!(
+        decode_m3(&config, &bytes),
+        Err(AkeError::Decoding("message type"))
+    );
+
+    assert_eq!(
+        decode_m1(&config, &bytes[..bytes.len() - 1]),
+        Err(AkeError::Decoding("truncated message"))
+    );
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert_eq!(
+        decode_m1(&config, &trailing),
+        Err(AkeError::Decoding("trailing bytes"))
+    );
+
+    assert!(peek_header(&[PROTOCOL_VERSION, 2]).is_err());
+    assert!(peek_header(&[PROTOCOL_VERSION, 2, 9]).is_err());
+}
+
+#[test]
+fn wire_m1_is_only_the_commitment() {
+    let config = classic::config::Config::new(4);
+    let (_, mut clients) = registration(&config, &mut OsRng).unwrap();
+
+    // m1 of the first and last client have the same length and layout: the
+    // header and comm_i, nothing that depends on i
+    let first = round_1(&mut clients[0], &config, &mut OsRng).unwrap();
+    let last = round_1(&mut clients[3], &config, &mut OsRng).unwrap();
+    let first_bytes = encode_m1(&config, &first);
+    let last_bytes = encode_m1(&config, &last);
+
+    assert_eq!(first_bytes.len(), last_bytes.len());
+    assert_eq!(first_bytes.len(), 3 + 4 + first.len());
+    assert_eq!(&first_bytes[7..], &first[..]);
+}